@@ -0,0 +1,214 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::path::{Path, PathBuf};
+use crate::kad::server::DEFAULT_VERSION;
+use crate::kad::socket_options::SocketOptions;
+use crate::lookup::iterative_lookup::DEFAULT_ALPHA;
+use crate::refresh::refresh_handler::DEFAULT_REFRESH_TIME;
+use crate::refresh::tasks::bucket_refresh_task::BUCKET_REFRESH_TIME;
+use crate::routing::bucket_types::BucketTypes;
+use crate::routing::kb::k_bucket::MAX_BUCKET_SIZE;
+use crate::rpc::response_tracker::STALLED_TIME;
+use crate::utils::node::QUERY_TIME;
+use crate::utils::spam_throttle::{BURST, PER_SECOND};
+use crate::utils::uid::{ID_LENGTH, UID};
+
+//RUNTIME TUNABLES, THE DEFAULTS MATCH THE CONSTANTS THE LIBRARY USED BEFORE THEY WERE CONFIGURABLE
+#[derive(Clone, Debug)]
+pub struct Config {
+    bucket_type: BucketTypes,
+    bind_address: Ipv4Addr,
+    bind_address6: Ipv6Addr,
+    socket_options: SocketOptions,
+    k: usize,
+    alpha: usize,
+    stalled_time: u128,
+    query_time: u128,
+    burst: usize,
+    per_second: usize,
+    refresh_time: u64,
+    bucket_refresh_time: u128,
+    secure_only: bool,
+    uid_seed: Option<[u8; ID_LENGTH]>,
+    uid: Option<UID>,
+    allow_bogon: bool,
+    version: Vec<u8>,
+    state_file: Option<PathBuf>
+}
+
+impl Config {
+
+    pub fn new() -> Self {
+        Self {
+            bucket_type: BucketTypes::Kademlia,
+            bind_address: Ipv4Addr::UNSPECIFIED,
+            bind_address6: Ipv6Addr::UNSPECIFIED,
+            socket_options: SocketOptions::new(),
+            k: MAX_BUCKET_SIZE,
+            alpha: DEFAULT_ALPHA,
+            stalled_time: STALLED_TIME,
+            query_time: QUERY_TIME,
+            burst: BURST,
+            per_second: PER_SECOND,
+            refresh_time: DEFAULT_REFRESH_TIME,
+            bucket_refresh_time: BUCKET_REFRESH_TIME,
+            secure_only: true,
+            uid_seed: None,
+            uid: None,
+            allow_bogon: false,
+            version: DEFAULT_VERSION.to_vec(),
+            state_file: None
+        }
+    }
+
+    pub fn set_bucket_type(&mut self, bucket_type: BucketTypes) {
+        self.bucket_type = bucket_type;
+    }
+
+    pub fn get_bucket_type(&self) -> BucketTypes {
+        self.bucket_type
+    }
+
+    pub fn set_bind_address(&mut self, bind_address: Ipv4Addr) {
+        self.bind_address = bind_address;
+    }
+
+    pub fn get_bind_address(&self) -> Ipv4Addr {
+        self.bind_address
+    }
+
+    pub fn set_bind_address6(&mut self, bind_address6: Ipv6Addr) {
+        self.bind_address6 = bind_address6;
+    }
+
+    pub fn get_bind_address6(&self) -> Ipv6Addr {
+        self.bind_address6
+    }
+
+    pub fn set_socket_options(&mut self, socket_options: SocketOptions) {
+        self.socket_options = socket_options;
+    }
+
+    pub fn get_socket_options(&self) -> &SocketOptions {
+        &self.socket_options
+    }
+
+    pub fn set_k(&mut self, k: usize) {
+        self.k = k.max(1);
+    }
+
+    pub fn get_k(&self) -> usize {
+        self.k
+    }
+
+    pub fn set_alpha(&mut self, alpha: usize) {
+        self.alpha = alpha.max(1);
+    }
+
+    pub fn get_alpha(&self) -> usize {
+        self.alpha
+    }
+
+    pub fn set_stalled_time(&mut self, stalled_time: u128) {
+        self.stalled_time = stalled_time;
+    }
+
+    pub fn get_stalled_time(&self) -> u128 {
+        self.stalled_time
+    }
+
+    pub fn set_query_time(&mut self, query_time: u128) {
+        self.query_time = query_time;
+    }
+
+    pub fn get_query_time(&self) -> u128 {
+        self.query_time
+    }
+
+    pub fn set_throttle_rate(&mut self, burst: usize, per_second: usize) {
+        self.burst = burst.max(1);
+        self.per_second = per_second;
+    }
+
+    pub fn get_burst(&self) -> usize {
+        self.burst
+    }
+
+    pub fn get_per_second(&self) -> usize {
+        self.per_second
+    }
+
+    pub fn set_refresh_time(&mut self, refresh_time: u64) {
+        self.refresh_time = refresh_time;
+    }
+
+    pub fn get_refresh_time(&self) -> u64 {
+        self.refresh_time
+    }
+
+    //HOW LONG A BUCKET HAS TO GO WITHOUT CHANGES BEFORE IT GETS A REFRESH LOOKUP
+    pub fn set_bucket_refresh_time(&mut self, bucket_refresh_time: u128) {
+        self.bucket_refresh_time = bucket_refresh_time;
+    }
+
+    pub fn get_bucket_refresh_time(&self) -> u128 {
+        self.bucket_refresh_time
+    }
+
+    pub fn set_secure_only(&mut self, secure_only: bool) {
+        self.secure_only = secure_only;
+    }
+
+    pub fn is_secure_only(&self) -> bool {
+        self.secure_only
+    }
+
+    //PERSIST THE SEED TO KEEP THE SAME BEP 42 ID ACROSS RESTARTS FOR THE SAME EXTERNAL ADDRESS
+    pub fn set_uid_seed(&mut self, uid_seed: [u8; ID_LENGTH]) {
+        self.uid_seed = Some(uid_seed);
+    }
+
+    pub fn get_uid_seed(&self) -> Option<[u8; ID_LENGTH]> {
+        self.uid_seed
+    }
+
+    //ONLY USED WHEN secure_only IS OFF
+    pub fn set_uid(&mut self, uid: UID) {
+        self.uid = Some(uid);
+    }
+
+    pub fn get_uid(&self) -> Option<UID> {
+        self.uid
+    }
+
+    pub fn set_allow_bogon(&mut self, allow_bogon: bool) {
+        self.allow_bogon = allow_bogon;
+    }
+
+    pub fn is_allow_bogon(&self) -> bool {
+        self.allow_bogon
+    }
+
+    pub fn set_version(&mut self, version: &[u8]) {
+        self.version = version.to_vec();
+    }
+
+    pub fn get_version(&self) -> &[u8] {
+        &self.version
+    }
+
+    //ROUTING TABLE STATE IS LOADED ON START, SAVED EVERY REFRESH AND ON STOP
+    pub fn set_state_file<P: AsRef<Path>>(&mut self, state_file: P) {
+        self.state_file = Some(state_file.as_ref().to_path_buf());
+    }
+
+    pub fn get_state_file(&self) -> Option<&Path> {
+        self.state_file.as_deref()
+    }
+}
+
+impl Default for Config {
+
+    fn default() -> Self {
+        Self::new()
+    }
+}
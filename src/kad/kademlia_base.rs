@@ -1,24 +1,54 @@
 use std::io;
 use std::net::SocketAddr;
 use std::sync::{Arc, Mutex};
+use crate::kad::config::Config;
 use crate::kad::server::Server;
+use crate::lookup::get_peers_lookup_handler::GetPeersResult;
+use crate::messages::ping_response::PingResponse;
 use crate::refresh::refresh_handler::RefreshHandler;
 use crate::routing::inter::routing_table::RoutingTable;
+use crate::rpc::query_future::QueryFuture;
+use crate::utils::node::Node;
+use crate::utils::uid::UID;
 
 pub trait KademliaBase: Send + Sync {
 
     fn bind(&self, port: u16) -> io::Result<()>;
 
+    fn bind_to(&self, address: SocketAddr) -> io::Result<()>;
+
     fn join(&self, local_port: u16, addr: SocketAddr) -> io::Result<()>;
 
-    fn stop(&self);
+    fn join_from(&self, local_address: SocketAddr, addr: SocketAddr) -> io::Result<()>;
+
+    //THE NODE IS STOPPED EVEN WHEN SAVING THE STATE FILE FAILS
+    fn stop(&self) -> io::Result<()>;
+
+    fn ping(&self, address: SocketAddr) -> QueryFuture<PingResponse>;
+
+    fn find_node(&self, target: UID) -> QueryFuture<Vec<Node>>;
+
+    fn get_peers(&self, info_hash: UID) -> QueryFuture<GetPeersResult>;
+
+    fn announce(&self, info_hash: UID, port: u16) -> QueryFuture<Vec<Node>>;
 
     fn get_server(&self) -> &Arc<Mutex<Server>>;
 
     fn get_routing_table(&self) -> &Arc<Mutex<dyn RoutingTable>>;
 
+    fn get_routing_table6(&self) -> &Arc<Mutex<dyn RoutingTable>>;
+
+    fn get_routing_table_for(&self, address: &SocketAddr) -> &Arc<Mutex<dyn RoutingTable>> {
+        match address {
+            SocketAddr::V4(_) => self.get_routing_table(),
+            SocketAddr::V6(_) => self.get_routing_table6()
+        }
+    }
+
     fn get_refresh_handler(&self) -> &Arc<Mutex<RefreshHandler>>;
 
+    fn get_config(&self) -> &Config;
+
     fn join_thread(&self);
 
     fn clone_dyn(&self) -> Box<dyn KademliaBase>;
@@ -0,0 +1,164 @@
+use std::io;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::path::Path;
+use crate::kad::config::Config;
+use crate::kad::socket_options::SocketOptions;
+use crate::kademlia::Kademlia;
+use crate::routing::bucket_types::BucketTypes;
+use crate::utils::uid::{ID_LENGTH, UID};
+
+pub struct KademliaBuilder {
+    config: Config
+}
+
+impl KademliaBuilder {
+
+    pub fn new() -> Self {
+        Self {
+            config: Config::default()
+        }
+    }
+
+    pub fn bucket_type(mut self, bucket_type: BucketTypes) -> Self {
+        self.config.set_bucket_type(bucket_type);
+        self
+    }
+
+    pub fn bind_address(mut self, bind_address: Ipv4Addr) -> Self {
+        self.config.set_bind_address(bind_address);
+        self
+    }
+
+    pub fn bind_address6(mut self, bind_address6: Ipv6Addr) -> Self {
+        self.config.set_bind_address6(bind_address6);
+        self
+    }
+
+    pub fn socket_options(mut self, socket_options: SocketOptions) -> Self {
+        self.config.set_socket_options(socket_options);
+        self
+    }
+
+    pub fn k(mut self, k: usize) -> Self {
+        self.config.set_k(k);
+        self
+    }
+
+    pub fn alpha(mut self, alpha: usize) -> Self {
+        self.config.set_alpha(alpha);
+        self
+    }
+
+    pub fn stalled_time(mut self, stalled_time: u128) -> Self {
+        self.config.set_stalled_time(stalled_time);
+        self
+    }
+
+    pub fn query_time(mut self, query_time: u128) -> Self {
+        self.config.set_query_time(query_time);
+        self
+    }
+
+    pub fn throttle_rate(mut self, burst: usize, per_second: usize) -> Self {
+        self.config.set_throttle_rate(burst, per_second);
+        self
+    }
+
+    pub fn refresh_time(mut self, refresh_time: u64) -> Self {
+        self.config.set_refresh_time(refresh_time);
+        self
+    }
+
+    pub fn bucket_refresh_time(mut self, bucket_refresh_time: u128) -> Self {
+        self.config.set_bucket_refresh_time(bucket_refresh_time);
+        self
+    }
+
+    pub fn secure_only(mut self, secure_only: bool) -> Self {
+        self.config.set_secure_only(secure_only);
+        self
+    }
+
+    pub fn uid_seed(mut self, uid_seed: [u8; ID_LENGTH]) -> Self {
+        self.config.set_uid_seed(uid_seed);
+        self
+    }
+
+    pub fn uid(mut self, uid: UID) -> Self {
+        self.config.set_uid(uid);
+        self
+    }
+
+    pub fn allow_bogon(mut self, allow_bogon: bool) -> Self {
+        self.config.set_allow_bogon(allow_bogon);
+        self
+    }
+
+    pub fn version(mut self, version: &[u8]) -> Self {
+        self.config.set_version(version);
+        self
+    }
+
+    pub fn state_file<P: AsRef<Path>>(mut self, state_file: P) -> Self {
+        self.config.set_state_file(state_file);
+        self
+    }
+
+    pub fn get_config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn build(self) -> io::Result<Kademlia> {
+        Kademlia::try_from(self.config)
+    }
+}
+
+impl Default for KademliaBuilder {
+
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<Config> for KademliaBuilder {
+
+    fn from(config: Config) -> Self {
+        Self {
+            config
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::kad::kademlia_base::KademliaBase;
+    use super::*;
+
+    #[test]
+    fn builder_config() {
+        let kademlia = KademliaBuilder::new()
+            .bucket_type(BucketTypes::MainLine)
+            .k(8)
+            .alpha(4)
+            .stalled_time(5000)
+            .secure_only(false)
+            .version(&[b'T', b'T', 0x00, 0x02])
+            .build().unwrap();
+
+        assert_eq!(kademlia.get_config().get_alpha(), 4);
+        assert_eq!(kademlia.get_routing_table().lock().unwrap().get_k(), 8);
+        assert_eq!(kademlia.get_routing_table6().lock().unwrap().get_k(), 8);
+        assert!(!kademlia.get_routing_table().lock().unwrap().is_secure_only());
+        assert_eq!(kademlia.get_server().lock().unwrap().get_stalled_time(), 5000);
+        assert_eq!(kademlia.get_server().lock().unwrap().get_version().as_slice(), &[b'T', b'T', 0x00, 0x02]);
+    }
+    #[test]
+    fn explicit_uid_requires_insecure() {
+        let uid = UID::from([1u8; ID_LENGTH]);
+        assert!(KademliaBuilder::new().uid(uid).build().is_err());
+
+        let kademlia = KademliaBuilder::new().secure_only(false).uid(uid).build().unwrap();
+        assert!(kademlia.get_routing_table().lock().unwrap().get_derived_uid() == uid);
+    }
+}
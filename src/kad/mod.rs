@@ -1,2 +1,5 @@
 pub mod kademlia_base;
 pub mod server;
+pub mod config;
+pub mod kademlia_builder;
+pub mod socket_options;
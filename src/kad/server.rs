@@ -1,22 +1,33 @@
-use std::cmp::min;
 use std::collections::HashMap;
-use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket};
 use std::sync::{Arc, Mutex};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::{io, thread};
+use std::io;
+use std::time::Duration;
+#[cfg(not(feature = "tokio"))]
+use std::thread;
+#[cfg(not(feature = "tokio"))]
 use std::sync::mpsc::{channel, Sender, TryRecvError};
-use std::thread::{sleep, JoinHandle};
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+#[cfg(not(feature = "tokio"))]
+use std::thread::JoinHandle;
+#[cfg(not(feature = "tokio"))]
+use std::time::{SystemTime, UNIX_EPOCH};
 use rlibbencode::variables::bencode_bytes::BencodeBytes;
-use rlibbencode::variables::bencode_object::{BencodeObject, GetObject, ObjectOptions};
+use rlibbencode::variables::bencode_number::BencodeNumber;
+use rlibbencode::variables::bencode_object::{BencodeObject, GetObject, ObjectOptions, PutObject};
 use rlibbencode::variables::inter::bencode_variable::{BencodeVariable, FromBencode, ToBencode};
+#[cfg(not(feature = "tokio"))]
+use mio::{Events, Interest, Poll, Token, Waker};
+use socket2::{Domain, Protocol, Socket, Type};
 use crate::kad::kademlia_base::KademliaBase;
+use crate::kad::socket_options::SocketOptions;
 use crate::messages::error_response::ErrorResponse;
 use crate::messages::inter::message_base::{MessageBase, TID_KEY};
 use crate::messages::inter::message_exception::MessageException;
 use crate::messages::inter::message_key::MessageKey;
 use crate::messages::inter::message_type::{MessageType, TYPE_KEY};
 use crate::messages::inter::method_message_base::MethodMessageBase;
+use crate::routing::inter::routing_table::insert_checked;
 use crate::rpc::call::Call;
 use crate::rpc::events::error_response_event::ErrorResponseEvent;
 use crate::rpc::events::inter::event::Event;
@@ -24,6 +35,7 @@ use crate::rpc::events::inter::message_event::MessageEvent;
 use crate::rpc::events::inter::response_callback::ResponseCallback;
 use crate::rpc::events::request_event::RequestEvent;
 use crate::rpc::events::response_event::ResponseEvent;
+use crate::rpc::events::stalled_event::StalledEvent;
 use crate::rpc::response_tracker::ResponseTracker;
 use crate::utils;
 use crate::utils::net::address_utils::is_bogon;
@@ -31,18 +43,48 @@ use crate::utils::node::Node;
 use crate::utils::spam_throttle::SpamThrottle;
 
 pub const TID_LENGTH: usize = 6;
+pub const READ_ONLY_KEY: &str = "ro";
+pub const VERSION_KEY: &str = "v";
+pub const VERSION_LENGTH: usize = 4;
+pub const DEFAULT_VERSION: [u8; VERSION_LENGTH] = [b'R', b'L', 0x00, 0x01];
+
+#[cfg(not(feature = "tokio"))]
+const SERVER_TOKEN: Token = Token(0);
+#[cfg(not(feature = "tokio"))]
+const SERVER6_TOKEN: Token = Token(1);
+#[cfg(not(feature = "tokio"))]
+const WAKER_TOKEN: Token = Token(2);
+const DECAY_INTERVAL: u64 = 1000;
+
+#[cfg(not(feature = "tokio"))]
+type SenderPool = Sender<(Vec<u8>, SocketAddr)>;
+#[cfg(feature = "tokio")]
+type SenderPool = tokio::sync::mpsc::UnboundedSender<(Vec<u8>, SocketAddr)>;
 
 pub struct Server {
     pub kademlia: Option<Box<dyn KademliaBase>>,
+    #[cfg(not(feature = "tokio"))]
     pub (crate) handle: Option<JoinHandle<()>>,
+    #[cfg(feature = "tokio")]
+    pub (crate) handle: Option<tokio::task::JoinHandle<()>>,
     server: Option<UdpSocket>,
+    server6: Option<UdpSocket>,
+    optional_bind_error: Option<io::Error>,
+    bind_address: Ipv4Addr,
+    bind_address6: Ipv6Addr,
+    socket_options: SocketOptions,
     allow_bogon: bool,
+    read_only: bool,
+    version: Vec<u8>,
     tracker: ResponseTracker,
     running: Arc<AtomicBool>, //MAY NOT BE NEEDED
-    tx_sender_pool: Option<Sender<(Vec<u8>, SocketAddr)>>,
+    tx_sender_pool: Option<SenderPool>,
+    #[cfg(not(feature = "tokio"))]
+    waker: Option<Arc<Waker>>,
     request_mapping: HashMap<String, Vec<Box<dyn Fn(&mut RequestEvent) + Send>>>,
     messages: HashMap<MessageKey, fn() -> Box<dyn MethodMessageBase>>,
-    sender_throttle: SpamThrottle
+    sender_throttle: SpamThrottle,
+    receiver_throttle: SpamThrottle
 }
 
 impl Server {
@@ -52,62 +94,157 @@ impl Server {
             kademlia: None,
             handle: None,
             server: None,
+            server6: None,
+            optional_bind_error: None,
+            bind_address: Ipv4Addr::UNSPECIFIED,
+            bind_address6: Ipv6Addr::UNSPECIFIED,
+            socket_options: SocketOptions::new(),
             allow_bogon: false,
+            read_only: false,
+            version: DEFAULT_VERSION.to_vec(),
             tracker: ResponseTracker::new(),
             running: Arc::new(AtomicBool::new(false)), //MAY NOT BE NEEDED
             tx_sender_pool: None,
+            #[cfg(not(feature = "tokio"))]
+            waker: None,
             request_mapping: HashMap::new(),
             messages: HashMap::new(),
-            sender_throttle: SpamThrottle::new()
+            sender_throttle: SpamThrottle::new(),
+            receiver_throttle: SpamThrottle::new()
         }
     }
 
     pub fn start(&mut self, port: u16) -> io::Result<()> {
+        self.start_on(SocketAddr::from((self.bind_address, port)))
+    }
+
+    //THE ADDRESS PINS THE SOCKET OF ITS OWN FAMILY, THE OTHER FAMILY IS OPTIONAL AND USES ITS BIND ADDRESS WITH THE SAME PORT
+    pub fn start_on(&mut self, address: SocketAddr) -> io::Result<()> {
         if self.is_running() {
             return Err(io::Error::new(io::ErrorKind::Other, "Server is already running"));
         }
 
+        match address {
+            SocketAddr::V4(_) => {
+                let server = self.bind_socket(address)?;
+                let port = server.local_addr()?.port();
+                self.server = Some(server);
+                self.server6 = self.bind_optional(SocketAddr::from((self.bind_address6, port)));
+            }
+            SocketAddr::V6(_) => {
+                let server6 = self.bind_socket(address)?;
+                let port = server6.local_addr()?.port();
+                self.server6 = Some(server6);
+                self.server = self.bind_optional(SocketAddr::from((self.bind_address, port)));
+            }
+        }
+
         self.running.store(true, Ordering::Relaxed);
 
-        self.server = Some(UdpSocket::bind(SocketAddr::from((Ipv4Addr::UNSPECIFIED, port)))?);
-        self.server.as_ref().unwrap().set_nonblocking(true)?;
+        self.handle = Some(self.spawn()?);
+
+        Ok(())
+    }
+
+    pub fn get_local_address(&self) -> Option<SocketAddr> {
+        self.server.as_ref().and_then(|server| server.local_addr().ok())
+    }
+
+    pub fn get_local_address6(&self) -> Option<SocketAddr> {
+        self.server6.as_ref().and_then(|server6| server6.local_addr().ok())
+    }
+
+    //BLOCKS ON SOCKET READINESS, OUTGOING MESSAGES WAKE THE POLL THROUGH THE WAKER
+    #[cfg(not(feature = "tokio"))]
+    fn spawn(&mut self) -> io::Result<JoinHandle<()>> {
+        let mut poll = Poll::new()?;
+        let waker = Arc::new(Waker::new(poll.registry(), WAKER_TOKEN)?);
+
+        let server = match &self.server {
+            Some(server) => {
+                let mut server = mio::net::UdpSocket::from_std(server.try_clone()?);
+                poll.registry().register(&mut server, SERVER_TOKEN, Interest::READABLE)?;
+                Some(server)
+            }
+            None => None
+        };
+
+        let server6 = match &self.server6 {
+            Some(server6) => {
+                let mut server6 = mio::net::UdpSocket::from_std(server6.try_clone()?);
+                poll.registry().register(&mut server6, SERVER6_TOKEN, Interest::READABLE)?;
+                Some(server6)
+            }
+            None => None
+        };
 
         let (tx_sender_pool, rx_sender_pool) = channel();
         self.tx_sender_pool = Some(tx_sender_pool);
+        self.waker = Some(waker);
 
-        self.handle = Some(thread::spawn({
+        Ok(thread::spawn({
             let kademlia = self.kademlia.clone();
-            let server = self.server.as_ref().unwrap().try_clone()?;
             let running = Arc::clone(&self.running);
-            let receiver_throttle = SpamThrottle::new();
+            let receiver_throttle = self.receiver_throttle.clone();
 
             move || {
                 let mut kademlia = kademlia.unwrap();
                 let mut buf = [0u8; 65535];
+                let mut events = Events::with_capacity(64);
                 let mut last_decay_time = SystemTime::now()
                     .duration_since(UNIX_EPOCH)
                     .expect("Time went backwards")
                     .as_millis();
 
-                while running.load(Ordering::Relaxed) {
-                    match server.recv_from(&mut buf) {
-                        Ok((size, src_addr)) => {
-                            if !receiver_throttle.add_and_test(src_addr.ip()) {
-                                Self::on_receive(kademlia.as_mut(), buf[..size].to_vec().as_slice(), src_addr);
+                'running: while running.load(Ordering::Relaxed) {
+                    let now = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .expect("Time went backwards")
+                        .as_millis();
+                    let elapsed = (now - last_decay_time).min(DECAY_INTERVAL as u128) as u64;
+
+                    match poll.poll(&mut events, Some(Duration::from_millis(DECAY_INTERVAL-elapsed))) {
+                        Ok(_) => {}
+                        Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                        Err(_) => break
+                    }
+
+                    for event in events.iter() {
+                        let socket = match (event.token(), &server, &server6) {
+                            (SERVER_TOKEN, Some(server), _) => server,
+                            (SERVER6_TOKEN, _, Some(server6)) => server6,
+                            _ => continue
+                        };
+
+                        //READINESS IS EDGE TRIGGERED, EVERY PENDING DATAGRAM HAS TO BE READ BEFORE POLLING AGAIN
+                        loop {
+                            match socket.recv_from(&mut buf) {
+                                Ok((size, src_addr)) => {
+                                    if !receiver_throttle.add_and_test(src_addr.ip()) {
+                                        Self::on_receive(kademlia.as_mut(), &buf[..size], src_addr);
+                                    }
+                                }
+                                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                                _ => break 'running
                             }
                         }
-                        Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {}
-                        _ => break
                     }
 
-                    match rx_sender_pool.try_recv() {
-                        Ok((data, dst_addr)) => {
-                            if !kademlia.get_server().lock().unwrap().sender_throttle.test(dst_addr.ip()) {
-                                server.send_to(data.as_slice(), dst_addr);
+                    loop {
+                        match rx_sender_pool.try_recv() {
+                            Ok((data, dst_addr)) => {
+                                if !kademlia.get_server().lock().unwrap().sender_throttle.test(dst_addr.ip()) {
+                                    let _ = match (dst_addr, &server, &server6) {
+                                        (SocketAddr::V4(_), Some(server), _) => server.send_to(data.as_slice(), dst_addr),
+                                        (SocketAddr::V6(_), _, Some(server6)) => server6.send_to(data.as_slice(), dst_addr),
+                                        _ => Ok(0)
+                                    };
+                                }
                             }
+                            Err(TryRecvError::Empty) => break,
+                            Err(TryRecvError::Disconnected) => break 'running
                         }
-                        Err(TryRecvError::Empty) => {}
-                        Err(TryRecvError::Disconnected) => break
                     }
 
                     let now = SystemTime::now()
@@ -115,26 +252,163 @@ impl Server {
                         .expect("Time went backwards")
                         .as_millis();
 
-                    if now - last_decay_time >= 1000 {
+                    if now - last_decay_time >= DECAY_INTERVAL as u128 {
                         receiver_throttle.decay();
                         kademlia.get_server().lock().unwrap().sender_throttle.decay();
-                        kademlia.get_server().lock().unwrap().tracker.remove_stalled();
+
+                        let stalled = kademlia.get_server().lock().unwrap().tracker.remove_stalled();
+                        for call in stalled {
+                            let mut event = StalledEvent::new(call.get_message().upcast());
+                            event.set_sent_time(call.get_sent_time());
+
+                            if call.has_node() {
+                                event.set_node(call.get_node());
+                            }
+
+                            call.get_response_callback().on_stalled(event);
+                        }
 
                         last_decay_time = now;
                     }
+                }
+            }
+        }))
+    }
+
+    //RUNS THE SOCKET LOOP AS A TASK ON THE CURRENT TOKIO RUNTIME, SO start HAS TO BE CALLED FROM WITHIN ONE
+    #[cfg(feature = "tokio")]
+    fn spawn(&mut self) -> io::Result<tokio::task::JoinHandle<()>> {
+        let runtime = tokio::runtime::Handle::try_current().map_err(io::Error::other)?;
+        let _guard = runtime.enter();
 
-                    sleep(Duration::from_millis(1));
+        let (tx_sender_pool, mut rx_sender_pool) = tokio::sync::mpsc::unbounded_channel::<(Vec<u8>, SocketAddr)>();
+        self.tx_sender_pool = Some(tx_sender_pool);
+
+        let kademlia = self.kademlia.clone();
+        let server = match &self.server {
+            Some(server) => Some(tokio::net::UdpSocket::from_std(server.try_clone()?)?),
+            None => None
+        };
+        let server6 = match &self.server6 {
+            Some(server6) => Some(tokio::net::UdpSocket::from_std(server6.try_clone()?)?),
+            None => None
+        };
+        let running = Arc::clone(&self.running);
+        let receiver_throttle = self.receiver_throttle.clone();
+
+        Ok(runtime.spawn(async move {
+            let mut kademlia = kademlia.unwrap();
+            let mut buf = [0u8; 65535];
+            let mut buf6 = [0u8; 65535];
+            let mut interval = tokio::time::interval(Duration::from_millis(DECAY_INTERVAL));
+
+            while running.load(Ordering::Relaxed) {
+                tokio::select! {
+                    received = Self::recv_from_optional(server.as_ref(), &mut buf) => match received {
+                        Ok((size, src_addr)) => {
+                            if !receiver_throttle.add_and_test(src_addr.ip()) {
+                                Self::on_receive(kademlia.as_mut(), &buf[..size], src_addr);
+                            }
+                        }
+                        Err(_) => break
+                    },
+                    received = Self::recv_from_optional(server6.as_ref(), &mut buf6) => match received {
+                        Ok((size, src_addr)) => {
+                            if !receiver_throttle.add_and_test(src_addr.ip()) {
+                                Self::on_receive(kademlia.as_mut(), &buf6[..size], src_addr);
+                            }
+                        }
+                        Err(_) => break
+                    },
+                    message = rx_sender_pool.recv() => match message {
+                        Some((data, dst_addr)) => {
+                            if !kademlia.get_server().lock().unwrap().sender_throttle.test(dst_addr.ip()) {
+                                let _ = match (dst_addr, &server, &server6) {
+                                    (SocketAddr::V4(_), Some(server), _) => server.send_to(data.as_slice(), dst_addr).await,
+                                    (SocketAddr::V6(_), _, Some(server6)) => server6.send_to(data.as_slice(), dst_addr).await,
+                                    _ => Ok(0)
+                                };
+                            }
+                        }
+                        None => break
+                    },
+                    _ = interval.tick() => {
+                        receiver_throttle.decay();
+                        kademlia.get_server().lock().unwrap().sender_throttle.decay();
+
+                        let stalled = kademlia.get_server().lock().unwrap().tracker.remove_stalled();
+                        for call in stalled {
+                            let mut event = StalledEvent::new(call.get_message().upcast());
+                            event.set_sent_time(call.get_sent_time());
+
+                            if call.has_node() {
+                                event.set_node(call.get_node());
+                            }
+
+                            call.get_response_callback().on_stalled(event);
+                        }
+                    }
                 }
             }
-        }));
+        }))
+    }
 
-        Ok(())
+    #[cfg(feature = "tokio")]
+    async fn recv_from_optional(socket: Option<&tokio::net::UdpSocket>, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        match socket {
+            Some(socket) => socket.recv_from(buf).await,
+            None => std::future::pending().await
+        }
+    }
+
+    fn bind_socket(&self, address: SocketAddr) -> io::Result<UdpSocket> {
+        let socket = Socket::new(Domain::for_address(address), Type::DGRAM, Some(Protocol::UDP))?;
+
+        if address.is_ipv6() {
+            socket.set_only_v6(true)?;
+        }
+
+        socket.set_nonblocking(true)?;
+        self.socket_options.apply(&socket, &address)?;
+        socket.bind(&address.into())?;
+        Ok(socket.into())
+    }
+
+    //HOSTS WITHOUT THE OTHER FAMILY STAY SINGLE STACK, THE ERROR IS KEPT FOR get_optional_bind_error
+    fn bind_optional(&mut self, address: SocketAddr) -> Option<UdpSocket> {
+        match self.bind_socket(address) {
+            Ok(socket) => {
+                self.optional_bind_error = None;
+                Some(socket)
+            }
+            Err(e) => {
+                self.optional_bind_error = Some(e);
+                None
+            }
+        }
+    }
+
+    //WHY THE OTHER ADDRESS FAMILY COULDNT BE BOUND ON THE LAST START, NONE WHEN IT WAS
+    pub fn get_optional_bind_error(&self) -> Option<&io::Error> {
+        self.optional_bind_error.as_ref()
     }
 
     pub fn stop(&self) {
         self.running.store(false, Ordering::Relaxed);
+        self.wake();
     }
 
+    #[cfg(not(feature = "tokio"))]
+    fn wake(&self) {
+        if let Some(waker) = &self.waker {
+            waker.wake().ok();
+        }
+    }
+
+    //THE TOKIO CHANNEL AND SOCKETS WAKE THE TASK ON THEIR OWN
+    #[cfg(feature = "tokio")]
+    fn wake(&self) {}
+
     pub fn register_request_listener<F>(&mut self, key: &str, callback: F)
     where
         F: Fn(&mut RequestEvent) + Send + 'static
@@ -166,6 +440,73 @@ impl Server {
         self.allow_bogon = allow_bogon;
     }
 
+    pub fn set_bind_address(&mut self, bind_address: Ipv4Addr) {
+        self.bind_address = bind_address;
+    }
+
+    pub fn get_bind_address(&self) -> Ipv4Addr {
+        self.bind_address
+    }
+
+    pub fn set_bind_address6(&mut self, bind_address6: Ipv6Addr) {
+        self.bind_address6 = bind_address6;
+    }
+
+    pub fn get_bind_address6(&self) -> Ipv6Addr {
+        self.bind_address6
+    }
+
+    pub fn set_socket_options(&mut self, socket_options: SocketOptions) {
+        self.socket_options = socket_options;
+    }
+
+    pub fn get_socket_options(&self) -> &SocketOptions {
+        &self.socket_options
+    }
+
+    pub fn set_stalled_time(&mut self, stalled_time: u128) {
+        self.tracker.set_stalled_time(stalled_time);
+    }
+
+    pub fn get_stalled_time(&self) -> u128 {
+        self.tracker.get_stalled_time()
+    }
+
+    //ONLY TAKES EFFECT FOR THE RECEIVER IF SET BEFORE start
+    pub fn set_throttle_rate(&mut self, burst: usize, per_second: usize) {
+        self.sender_throttle = SpamThrottle::with_rate(burst, per_second);
+        self.receiver_throttle = SpamThrottle::with_rate(burst, per_second);
+    }
+
+    pub fn is_dual_stack(&self) -> bool {
+        self.server.is_some() && self.server6.is_some()
+    }
+
+    pub fn has_ipv4(&self) -> bool {
+        self.server.is_some()
+    }
+
+    pub fn has_ipv6(&self) -> bool {
+        self.server6.is_some()
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    //BY CONVENTION 2 BYTE CLIENT CODE FOLLOWED BY A 2 BYTE BIG ENDIAN VERSION
+    pub fn set_version(&mut self, version: &[u8]) {
+        self.version = version.to_vec();
+    }
+
+    pub fn get_version(&self) -> &Vec<u8> {
+        &self.version
+    }
+
     pub fn on_receive(kademlia: &mut dyn KademliaBase, data: &[u8], src_addr: SocketAddr) {
         if !kademlia.get_server().lock().unwrap().allow_bogon && is_bogon(src_addr) {
             return;
@@ -179,9 +520,15 @@ impl Server {
                 }
 
                 let t = MessageType::from_rpc_type_name(ben.get::<BencodeBytes>(TYPE_KEY).unwrap().to_string()).unwrap();
+                let version = ben.get::<BencodeBytes>(VERSION_KEY).map(|v| v.as_bytes().to_vec());
 
                 match t {
                     MessageType::ReqMsg => {
+                        //READ ONLY NODES DONT ANSWER QUERIES
+                        if kademlia.get_server().lock().unwrap().read_only {
+                            return;
+                        }
+
                         if let Err(e) = || -> Result<(), MessageException> {
                             let message_key = MessageKey::new(ben.get::<BencodeBytes>(t.rpc_type_name())
                                     .ok_or_else(|| MessageException::new("Method Unknown", 204))?.as_str(), t);
@@ -189,18 +536,24 @@ impl Server {
                             let mut m = kademlia.get_server().lock().as_ref().unwrap().messages.get(&message_key).ok_or(MessageException::new("Method Unknown", 204))?();
                             //let mut m = constructor();
 
-                            let mut tid = [0u8; TID_LENGTH];
-                            let slice = ben.get::<BencodeBytes>(TID_KEY).ok_or_else(|| MessageException::new("Method Unknown", 204))?.as_bytes();
-                            let len_to_copy = min(slice.len(), TID_LENGTH);
-                            tid[..len_to_copy].copy_from_slice(&slice[..len_to_copy]);
-                            //tid.copy_from_slice(ben.get_bytes(TID_KEY).map_err(|e| MessageException::new("Method Unknown", 204))?);
+                            let tid = ben.get::<BencodeBytes>(TID_KEY).ok_or_else(|| MessageException::new("Method Unknown", 204))?.as_bytes().to_vec();
 
-                            m.set_transaction_id(tid);
+                            m.set_transaction_id(&tid);
                             m.decode(&ben)?;
                             m.set_origin(src_addr);
 
-                            let node = Node::new(m.get_uid().unwrap(), m.get_origin().unwrap());
-                            kademlia.get_routing_table().lock().unwrap().insert(node);
+                            let mut node = Node::new(m.get_uid().unwrap(), m.get_origin().unwrap());
+                            if let Some(version) = version.as_ref().and_then(|v| <[u8; VERSION_LENGTH]>::try_from(v.as_slice()).ok()) {
+                                node.set_version(version);
+                            }
+                            node.seen();
+
+                            //READ ONLY SENDERS CANT ANSWER QUERIES SO THEY DONT BELONG IN THE ROUTING TABLE
+                            let read_only = ben.get::<BencodeNumber>(READ_ONLY_KEY).is_some_and(|ro| ro.parse::<i64>().is_ok_and(|ro| ro == 1));
+
+                            if !read_only {
+                                insert_checked(kademlia.get_routing_table_for(&src_addr), node);
+                            }
                             println!("SEEN REQ {}", node.to_string());
 
                             let k = ben.get::<BencodeBytes>(t.rpc_type_name()).unwrap().to_string();
@@ -212,10 +565,18 @@ impl Server {
                             let mut event = RequestEvent::new(m.upcast());
                             event.set_node(node);
 
+                            if let Some(version) = &version {
+                                event.set_version(version);
+                            }
+
                             for callback in kademlia.get_server().lock().as_ref().unwrap().request_mapping.get(&k).unwrap() {
                                 callback(&mut event);
                             }
 
+                            if let Some(e) = event.take_exception() {
+                                return Err(e);
+                            }
+
                             if event.is_prevent_default() {
                                 //RETURN NOTHING - NO ERROR
                                 return Err(MessageException::new("Method Unknown", 204));
@@ -232,12 +593,9 @@ impl Server {
                         }() {
                             //println!("{}", ben.to_string());
 
-                            let mut tid = [0u8; TID_LENGTH];
-                            let slice = ben.get::<BencodeBytes>(TID_KEY).unwrap().as_bytes();
-                            let len_to_copy = min(slice.len(), TID_LENGTH);
-                            tid[..len_to_copy].copy_from_slice(&slice[..len_to_copy]);
+                            let tid = ben.get::<BencodeBytes>(TID_KEY).map(|tid| tid.as_bytes().to_vec()).unwrap_or_default();
 
-                            let mut response = ErrorResponse::new(tid);
+                            let mut response = ErrorResponse::new(&tid);
                             response.set_destination(src_addr);
                             response.set_public(src_addr);
                             response.set_code(e.get_code());
@@ -246,16 +604,11 @@ impl Server {
                             kademlia.get_server().lock().as_ref().unwrap().send(&mut response).unwrap();
                         }
 
-                        if !kademlia.get_refresh_handler().lock().unwrap().is_running() {
-                            kademlia.get_refresh_handler().lock().unwrap().start();
-                        }
+                        kademlia.get_refresh_handler().lock().unwrap().resume();
                     },
                     MessageType::RspMsg => {
                         if let Err(e) = || -> Result<(), MessageException> {
-                            let mut tid = [0u8; TID_LENGTH];
-                            let slice = ben.get::<BencodeBytes>(TID_KEY).ok_or_else(|| MessageException::new("Method Unknown", 204))?.as_bytes();
-                            let len_to_copy = min(slice.len(), TID_LENGTH);
-                            tid[..len_to_copy].copy_from_slice(&slice[..len_to_copy]);
+                            let tid = ben.get::<BencodeBytes>(TID_KEY).ok_or_else(|| MessageException::new("Method Unknown", 204))?.as_bytes().to_vec();
 
                             let call = kademlia.get_server().lock().as_mut().unwrap().tracker.poll(&tid).ok_or(MessageException::new("Server Error", 202))?;
 
@@ -264,30 +617,42 @@ impl Server {
 
                             let mut m = kademlia.get_server().lock().as_ref().unwrap().messages.get(&message_key).ok_or(MessageException::new("Method Unknown", 204))?();
 
-                            m.set_transaction_id(tid);
+                            m.set_transaction_id(&tid);
                             m.decode(&ben)?;
                             m.set_origin(src_addr);
 
                             if m.get_public().is_some() {
-                                let update = kademlia.get_routing_table().lock().unwrap().get_update_public_ip_consensus();
-                                update(kademlia.get_routing_table().clone(), m.get_origin().unwrap().ip(), m.get_public().unwrap().ip());
+                                let update = kademlia.get_routing_table_for(&src_addr).lock().unwrap().get_update_public_ip_consensus();
+                                update(kademlia.get_routing_table_for(&src_addr).clone(), m.get_origin().unwrap().ip(), m.get_public().unwrap().ip());
                             }
 
                             if call.get_message().get_destination() != m.get_origin() {
                                 return Err(MessageException::new("Generic Error", 201));
                             }
 
-                            let mut event;
+                            let mut node;
 
                             if call.has_node() {
                                 if call.get_node().uid != m.get_uid().unwrap() {
                                     return Err(MessageException::new("Generic Error", 201));
                                 }
 
-                                event = ResponseEvent::new(m.as_ref().upcast(), call.get_node());
+                                node = call.get_node();
 
                             } else {
-                                event = ResponseEvent::new(m.as_ref().upcast(), Node::new(m.get_uid().unwrap(), m.get_origin().unwrap()));
+                                node = Node::new(m.get_uid().unwrap(), m.get_origin().unwrap());
+                            }
+
+                            node.responded();
+
+                            if let Some(version) = version.as_ref().and_then(|v| <[u8; VERSION_LENGTH]>::try_from(v.as_slice()).ok()) {
+                                node.set_version(version);
+                            }
+
+                            let mut event = ResponseEvent::new(m.as_ref().upcast(), node);
+
+                            if let Some(version) = &version {
+                                event.set_version(version);
                             }
 
                             event.received();
@@ -301,25 +666,28 @@ impl Server {
                         }() {
                             println!("{}", e.get_message());
                         }
+
+                        //READ ONLY NODES NEVER RECEIVE QUERIES SO THE REFRESH HAS TO START FROM RESPONSES
+                        let read_only = kademlia.get_server().lock().unwrap().read_only;
+                        if read_only {
+                            kademlia.get_refresh_handler().lock().unwrap().resume();
+                        }
                     },
                     MessageType::ErrMsg => {
                         //println!("ERR  {}", ben.to_string());
 
                         if let Err(e) = || -> Result<(), MessageException> {
-                            let mut tid = [0u8; TID_LENGTH];
-                            let slice = ben.get::<BencodeBytes>(TID_KEY).ok_or_else(|| MessageException::new("Method Unknown", 204))?.as_bytes();
-                            let len_to_copy = min(slice.len(), TID_LENGTH);
-                            tid[..len_to_copy].copy_from_slice(&slice[..len_to_copy]);
+                            let tid = ben.get::<BencodeBytes>(TID_KEY).ok_or_else(|| MessageException::new("Method Unknown", 204))?.as_bytes().to_vec();
 
                             let call = kademlia.get_server().lock().as_mut().unwrap().tracker.poll(&tid).ok_or(MessageException::new("Server Error", 202))?;
 
-                            let mut m = ErrorResponse::new(tid);
+                            let mut m = ErrorResponse::new(&tid);
                             m.decode(&ben)?;
                             m.set_origin(src_addr);
 
                             if m.get_public().is_some() {
-                                let update = kademlia.get_routing_table().lock().unwrap().get_update_public_ip_consensus();
-                                update(kademlia.get_routing_table().clone(), m.get_origin().unwrap().ip(), m.get_public().unwrap().ip());
+                                let update = kademlia.get_routing_table_for(&src_addr).lock().unwrap().get_update_public_ip_consensus();
+                                update(kademlia.get_routing_table_for(&src_addr).clone(), m.get_origin().unwrap().ip(), m.get_public().unwrap().ip());
                             }
 
                             if call.get_message().get_destination() != m.get_origin() {
@@ -361,15 +729,31 @@ impl Server {
             return Err(io::Error::new(io::ErrorKind::InvalidData, "Message destination set to bogon"));
         }
 
+        if message.get_destination().unwrap().is_ipv4() && self.server.is_none() {
+            return Err(io::Error::new(io::ErrorKind::AddrNotAvailable, "No IPv4 socket bound"));
+        }
+
+        if message.get_destination().unwrap().is_ipv6() && self.server6.is_none() {
+            return Err(io::Error::new(io::ErrorKind::AddrNotAvailable, "No IPv6 socket bound"));
+        }
+
         if message.get_type() != MessageType::ErrMsg {
-            message.set_uid(self.kademlia.as_ref().unwrap().get_routing_table().lock().unwrap().get_derived_uid());
+            message.set_uid(self.kademlia.as_ref().unwrap().get_routing_table_for(&message.get_destination().unwrap()).lock().unwrap().get_derived_uid());
         }
 
         //if let Some(server) = &self.server {
         //    server.send_to(message.encode().encode().as_slice(), message.get_destination().unwrap()).map_err(|e| e.to_string())?;
         //}
+        let mut ben = message.encode();
+        ben.put(VERSION_KEY, self.version.clone());
+
+        if self.read_only && message.get_type() == MessageType::ReqMsg {
+            ben.put(READ_ONLY_KEY, 1);
+        }
+
         if !self.sender_throttle.add_and_test(message.get_destination().unwrap().ip()) {
-            self.tx_sender_pool.as_ref().unwrap().send((message.encode().to_bencode(), message.get_destination().unwrap())).unwrap();
+            self.tx_sender_pool.as_ref().unwrap().send((ben.to_bencode(), message.get_destination().unwrap())).unwrap();
+            self.wake();
         }
 
         Ok(())
@@ -381,9 +765,9 @@ impl Server {
         }
 
         let tid = self.generate_transaction_id();
-        message.set_transaction_id(tid);
-        self.tracker.add(tid, Call::new(message, callback));
-        self.send(message.upcast_mut())
+        message.set_transaction_id(&tid);
+        self.tracker.add(&tid, Call::new(message, callback));
+        self.send_tracked(message, &tid)
     }
 
     pub fn send_with_node_callback(&mut self, message: &mut dyn MethodMessageBase, node: Node, callback: Box<dyn ResponseCallback>) -> io::Result<()> {
@@ -392,11 +776,22 @@ impl Server {
         }
 
         let tid = self.generate_transaction_id();
-        message.set_transaction_id(tid);
+        message.set_transaction_id(&tid);
         let mut call = Call::new(message, callback);
         call.set_node(node);
-        self.tracker.add(tid, call);
-        self.send(message.upcast_mut())
+        self.tracker.add(&tid, call);
+        self.send_tracked(message, &tid)
+    }
+
+    //A CALL THAT WAS NEVER SENT CANT STALL, THE CALLER HANDLES THE ERROR INSTEAD OF ITS CALLBACK
+    fn send_tracked(&mut self, message: &mut dyn MethodMessageBase, tid: &[u8]) -> io::Result<()> {
+        let sent = self.send(message.upcast_mut());
+
+        if sent.is_err() {
+            self.tracker.remove(tid);
+        }
+
+        sent
     }
 
     pub fn generate_transaction_id(&self) -> [u8; TID_LENGTH] {
@@ -0,0 +1,121 @@
+use std::io;
+use std::net::SocketAddr;
+use socket2::Socket;
+
+//OPTIONAL OPTIONS APPLIED TO EACH UDP SOCKET BEFORE IT IS BOUND, UNSET ONES KEEP THE OS DEFAULTS
+#[derive(Clone, Default, Debug)]
+pub struct SocketOptions {
+    recv_buffer_size: Option<usize>,
+    send_buffer_size: Option<usize>,
+    tos: Option<u32>,
+    device: Option<String>
+}
+
+impl SocketOptions {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_recv_buffer_size(&mut self, recv_buffer_size: usize) {
+        self.recv_buffer_size = Some(recv_buffer_size);
+    }
+
+    pub fn get_recv_buffer_size(&self) -> Option<usize> {
+        self.recv_buffer_size
+    }
+
+    pub fn set_send_buffer_size(&mut self, send_buffer_size: usize) {
+        self.send_buffer_size = Some(send_buffer_size);
+    }
+
+    pub fn get_send_buffer_size(&self) -> Option<usize> {
+        self.send_buffer_size
+    }
+
+    //IP_TOS FOR IPV4, THE IPV6 TRAFFIC CLASS ON PLATFORMS THAT SUPPORT IT
+    pub fn set_tos(&mut self, tos: u32) {
+        self.tos = Some(tos);
+    }
+
+    pub fn get_tos(&self) -> Option<u32> {
+        self.tos
+    }
+
+    //SO_BINDTODEVICE, ONLY AVAILABLE ON LINUX AND ANDROID
+    pub fn set_device(&mut self, device: &str) {
+        self.device = Some(device.to_string());
+    }
+
+    pub fn get_device(&self) -> Option<&str> {
+        self.device.as_deref()
+    }
+
+    pub(crate) fn apply(&self, socket: &Socket, address: &SocketAddr) -> io::Result<()> {
+        if let Some(size) = self.recv_buffer_size {
+            socket.set_recv_buffer_size(size)?;
+        }
+
+        if let Some(size) = self.send_buffer_size {
+            socket.set_send_buffer_size(size)?;
+        }
+
+        if let Some(tos) = self.tos {
+            match address {
+                SocketAddr::V4(_) => socket.set_tos(tos)?,
+                SocketAddr::V6(_) => Self::set_tclass_v6(socket, tos)?
+            }
+        }
+
+        if let Some(device) = &self.device {
+            Self::bind_device(socket, device)?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(any(target_os = "android", target_os = "freebsd", target_os = "linux", target_os = "macos"))]
+    fn set_tclass_v6(socket: &Socket, tclass: u32) -> io::Result<()> {
+        socket.set_tclass_v6(tclass)
+    }
+
+    #[cfg(not(any(target_os = "android", target_os = "freebsd", target_os = "linux", target_os = "macos")))]
+    fn set_tclass_v6(_socket: &Socket, _tclass: u32) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "IPv6 traffic class is not supported on this platform"))
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    fn bind_device(socket: &Socket, device: &str) -> io::Result<()> {
+        socket.bind_device(Some(device.as_bytes()))
+    }
+
+    #[cfg(not(any(target_os = "android", target_os = "linux")))]
+    fn bind_device(_socket: &Socket, _device: &str) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "Binding to a device is not supported on this platform"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::kad::kademlia_base::KademliaBase;
+    use crate::kad::kademlia_builder::KademliaBuilder;
+    use super::*;
+
+    #[test]
+    fn bind_options() {
+        let mut socket_options = SocketOptions::new();
+        socket_options.set_recv_buffer_size(1 << 20);
+        socket_options.set_send_buffer_size(1 << 20);
+        socket_options.set_tos(0x10);
+
+        let kademlia = KademliaBuilder::new().socket_options(socket_options).build().unwrap();
+        kademlia.bind_to(SocketAddr::from(([127, 0, 0, 1], 0))).unwrap();
+
+        let local_address = kademlia.get_server().lock().unwrap().get_local_address().unwrap();
+        assert_eq!(local_address.ip(), SocketAddr::from(([127, 0, 0, 1], 0)).ip());
+        assert_ne!(local_address.port(), 0);
+
+        kademlia.stop().unwrap();
+    }
+}
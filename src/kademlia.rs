@@ -1,78 +1,178 @@
 use std::io;
+use rlibbencode::variables::inter::bencode_variable::ToBencode;
 use std::net::SocketAddr;
+use std::path::Path;
 use std::sync::{Arc, Mutex};
+use crate::kad::config::Config;
 use crate::kad::kademlia_base::KademliaBase;
 use crate::kad::server::Server;
+use crate::lookup::find_node_lookup_handler::FindNodeLookupHandler;
+use crate::lookup::get_item_lookup_handler::GetItemLookupHandler;
+use crate::lookup::get_peers_lookup_handler::{GetPeersLookupHandler, GetPeersResult};
+use crate::lookup::iterative_lookup::IterativeLookup;
+use crate::lookup::put_item_lookup_handler::PutItemLookupHandler;
+use crate::lookup::sample_infohashes_lookup_handler::{SampleInfohashesWalk, SampledNodes};
+use crate::messages::announce_peer_request::AnnouncePeerRequest;
+use crate::messages::announce_peer_response::AnnouncePeerResponse;
 use crate::messages::find_node_request::FindNodeRequest;
 use crate::messages::find_node_response::FindNodeResponse;
+use crate::messages::get_peers_request::GetPeersRequest;
+use crate::messages::get_peers_response::GetPeersResponse;
+use crate::messages::get_request::GetRequest;
+use crate::messages::get_response::GetResponse;
 use crate::messages::inter::message_base::MessageBase;
+use crate::messages::inter::message_exception::MessageException;
 use crate::messages::ping_request::PingRequest;
 use crate::messages::ping_response::PingResponse;
+use crate::messages::put_request::PutRequest;
+use crate::messages::put_response::PutResponse;
+use crate::messages::sample_infohashes_request::SampleInfohashesRequest;
+use crate::messages::sample_infohashes_response::SampleInfohashesResponse;
 use crate::refresh::refresh_handler::RefreshHandler;
-use crate::refresh::tasks::bucket_refresh_task::BucketRefreshTask;
+use crate::refresh::tasks::bucket_refresh_task::{BUCKET_REFRESH_PACE, BucketRefreshTask};
+use crate::refresh::tasks::peer_expire_task::{PEER_EXPIRE_INTERVAL, PeerExpireTask};
+use crate::refresh::tasks::save_state_task::SaveStateTask;
 use crate::refresh::tasks::stale_refresh_task::StaleRefreshTask;
 use crate::routing::bucket_types::BucketTypes;
 use crate::routing::inter::routing_table::RoutingTable;
-use crate::routing::kb::k_bucket::MAX_BUCKET_SIZE;
-use crate::routing::kb::k_routing_table::KRoutingTable;
+use crate::routing::imported_state::ImportedState;
+use crate::routing::routing_state::RoutingState;
 use crate::rpc::events::inter::event::Event;
 use crate::rpc::events::inter::message_event::MessageEvent;
+use crate::rpc::announce_response_listener::AnnounceResponseListener;
 use crate::rpc::join_node_response_listener::JoinNodeResponseListener;
+use crate::rpc::ping_response_listener::PingResponseListener;
+use crate::rpc::query_future::{query_channel, QueryFuture};
+#[cfg(feature = "tokio")]
+use crate::rpc::query_future::QueryError;
+use crate::rpc::query_response_listener::QueryResponseListener;
+use crate::storage::item_store::{mutable_target, signature_buffer, ItemStore, StoredItem, MAX_SALT_SIZE, MAX_VALUE_SIZE};
+use crate::storage::peer_store::{PeerStore, MAX_VALUES};
+use crate::utils::bloom_filter::ScrapeEstimate;
+use crate::utils::ed25519::{verify, KeyPair, PUBLIC_KEY_LENGTH};
+use crate::utils::hash::sha1::sha1;
+use crate::utils::net::address_types::AddressTypes;
+use crate::utils::node::Node;
+use crate::utils::token_manager::TokenManager;
+use crate::utils::uid::UID;
 
 #[derive(Clone)]
 pub struct Kademlia {
     routing_table: Arc<Mutex<dyn RoutingTable>>,
+    routing_table6: Arc<Mutex<dyn RoutingTable>>,
     server: Arc<Mutex<Server>>,
-    refresh: Arc<Mutex<RefreshHandler>>
+    refresh: Arc<Mutex<RefreshHandler>>,
+    peer_store: Arc<Mutex<PeerStore>>,
+    item_store: Arc<Mutex<ItemStore>>,
+    token_manager: Arc<Mutex<TokenManager>>,
+    sampled: Arc<Mutex<SampledNodes>>,
+    config: Config
 }
 
-impl Default for Kademlia {
+impl TryFrom<Config> for Kademlia {
 
-    fn default() -> Self {
+    type Error = io::Error;
+
+    fn try_from(config: Config) -> io::Result<Self> {
         let mut server = Server::new();
+        server.set_bind_address(config.get_bind_address());
+        server.set_bind_address6(config.get_bind_address6());
+        server.set_socket_options(config.get_socket_options().clone());
+        server.set_allow_bogon(config.is_allow_bogon());
+        server.set_version(config.get_version());
+        server.set_stalled_time(config.get_stalled_time());
+        server.set_throttle_rate(config.get_burst(), config.get_per_second());
 
         server.register_message(|| Box::new(PingRequest::default()));
         server.register_message(|| Box::new(PingResponse::default()));
         server.register_message(|| Box::new(FindNodeRequest::default()));
         server.register_message(|| Box::new(FindNodeResponse::default()));
+        server.register_message(|| Box::new(GetPeersRequest::default()));
+        server.register_message(|| Box::new(GetPeersResponse::default()));
+        server.register_message(|| Box::new(AnnouncePeerRequest::default()));
+        server.register_message(|| Box::new(AnnouncePeerResponse::default()));
+        server.register_message(|| Box::new(GetRequest::default()));
+        server.register_message(|| Box::new(GetResponse::default()));
+        server.register_message(|| Box::new(PutRequest::default()));
+        server.register_message(|| Box::new(PutResponse::default()));
+        server.register_message(|| Box::new(SampleInfohashesRequest::default()));
+        server.register_message(|| Box::new(SampleInfohashesResponse::default()));
 
         server.register_request_listener("ping", move |event| {
             //println!("{}", event.get_message().to_string());
 
-            let mut response = PingResponse::new(*event.get_message().get_transaction_id());
+            let mut response = PingResponse::new(event.get_message().get_transaction_id());
             response.set_destination(event.get_message().get_origin().unwrap());
             response.set_public(event.get_message().get_origin().unwrap());
             event.set_response(Box::new(response));
         });
 
+        let refresh = RefreshHandler::new();
+        refresh.set_refresh_time(config.get_refresh_time());
+
         let _self = Self {
-            routing_table: Arc::new(Mutex::new(KRoutingTable::new())),
+            routing_table: config.get_bucket_type().routing_table(),
+            routing_table6: config.get_bucket_type().routing_table(),
             server: Arc::new(Mutex::new(server)),
-            refresh: Arc::new(Mutex::new(RefreshHandler::new()))
+            refresh: Arc::new(Mutex::new(refresh)),
+            peer_store: Arc::new(Mutex::new(PeerStore::new())),
+            item_store: Arc::new(Mutex::new(ItemStore::new())),
+            token_manager: Arc::new(Mutex::new(TokenManager::new())),
+            sampled: Arc::new(Mutex::new(SampledNodes::new())),
+            config
         };
 
-        _self.routing_table.lock().unwrap().add_restart_listener(Arc::new({
-            let _self = _self.clone();
-            move || {
-                let uid = _self.routing_table.lock().unwrap().get_derived_uid();
-                let closest = _self.routing_table.lock().unwrap().find_closest(&uid, MAX_BUCKET_SIZE);
+        for routing_table in [_self.routing_table.clone(), _self.routing_table6.clone()] {
+            routing_table.lock().unwrap().set_secure_only(_self.config.is_secure_only());
 
-                if closest.is_empty() {
-                    return;
+            if let Some(uid_seed) = _self.config.get_uid_seed() {
+                routing_table.lock().unwrap().set_uid_seed(uid_seed);
+            }
+
+            if let Some(uid) = _self.config.get_uid() {
+                routing_table.lock().unwrap().set_explicit_uid(uid)?;
+            }
+
+            routing_table.lock().unwrap().set_k(_self.config.get_k());
+            routing_table.lock().unwrap().set_query_time(_self.config.get_query_time());
+
+            routing_table.lock().unwrap().add_restart_listener(Arc::new({
+                let _self = _self.clone();
+                let routing_table = routing_table.clone();
+                move || {
+                    let uid = routing_table.lock().unwrap().get_derived_uid();
+                    let handler = FindNodeLookupHandler::new(routing_table.clone(), uid);
+
+                    //AN EMPTY TABLE HAS NOTHING TO RESTART FROM
+                    IterativeLookup::new(&_self, routing_table.clone(), uid, Arc::new(handler)).start().ok();
                 }
+            }));
+
+            //AN ANSWER MOVES THE NODE TO THE TAIL OF ITS BUCKET, A STALL SWAPS IN THE CANDIDATE
+            //A PING THAT CANT BE SENT COUNTS AS ONE THAT WASNT ANSWERED, OTHERWISE THE BUCKET WOULD WAIT ON IT FOREVER
+            routing_table.lock().unwrap().add_eviction_listener(Arc::new({
+                let _self = _self.clone();
+                let routing_table = routing_table.clone();
+                move |node| {
+                    let mut request = PingRequest::default();
+                    request.set_destination(node.address);
 
-                for n in closest {
-                    let mut request = FindNodeRequest::default();
-                    request.set_destination(n.address);
-                    request.set_target(_self.routing_table.lock().unwrap().get_derived_uid());
+                    let sent = _self.server.lock().unwrap().send_with_node_callback(&mut request, node, Box::new(PingResponseListener::new(routing_table.clone())));
 
-                    _self.server.lock().unwrap().send_with_callback(&mut request, Box::new(JoinNodeResponseListener::new(&_self))).unwrap();
+                    if sent.is_err() {
+                        routing_table.lock().unwrap().mark_stale(&node);
+                    }
                 }
-            }
-        }));
+            }));
+        }
 
-        _self.refresh.lock().unwrap().add_operation(Box::new(BucketRefreshTask::new(&_self)));
+        _self.refresh.lock().unwrap().add_operation_with_interval(Box::new(BucketRefreshTask::new(&_self)), BUCKET_REFRESH_PACE);
         _self.refresh.lock().unwrap().add_operation(Box::new(StaleRefreshTask::new(&_self)));
+        _self.refresh.lock().unwrap().add_operation_with_interval(Box::new(PeerExpireTask::new(&_self.peer_store)), PEER_EXPIRE_INTERVAL);
+
+        if _self.config.get_state_file().is_some() {
+            _self.refresh.lock().unwrap().add_operation(Box::new(SaveStateTask::new(&_self)));
+        }
 
         _self.server.lock().unwrap().register_request_listener("find_node", {
             let _self = _self.clone();
@@ -84,11 +184,10 @@ impl Default for Kademlia {
 
                 let request = event.get_message().as_any().downcast_ref::<FindNodeRequest>().unwrap();
 
-                let mut nodes = _self.get_routing_table().lock().unwrap()
-                    .find_closest(&request.get_target().unwrap(), MAX_BUCKET_SIZE);
+                let mut nodes = _self.find_closest_wanted(&request.get_target().unwrap(), &event.get_message().get_origin().unwrap(), request.get_want());
                 nodes.retain(|&n| n != event.get_node());
 
-                let mut response = FindNodeResponse::new(*event.get_message().get_transaction_id());
+                let mut response = FindNodeResponse::new(event.get_message().get_transaction_id());
                 response.set_destination(event.get_message().get_origin().unwrap());
                 response.set_public(event.get_message().get_origin().unwrap());
                 response.add_nodes(nodes);
@@ -96,185 +195,686 @@ impl Default for Kademlia {
             }
         });
 
+        _self.register_peer_listeners();
+        _self.register_item_listeners();
+
         _self.server.lock().unwrap().kademlia = Some(_self.clone_dyn());
 
-        _self
+        Ok(_self)
+    }
+}
+
+//ONLY AN EXPLICIT ID CAN FAIL THE CONFIG, THE DEFAULT ONE NEVER SETS ONE
+impl Default for Kademlia {
+
+    fn default() -> Self {
+        Self::try_from(Config::default()).expect("Default config has no explicit ID")
     }
 }
 
 impl From<BucketTypes> for Kademlia {
 
     fn from(bucket_type: BucketTypes) -> Self {
-        let mut server = Server::new();
+        let mut config = Config::default();
+        config.set_bucket_type(bucket_type);
+        Self::try_from(config).expect("Default config has no explicit ID")
+    }
+}
 
-        server.register_message(|| Box::new(PingRequest::default()));
-        server.register_message(|| Box::new(PingResponse::default()));
-        server.register_message(|| Box::new(FindNodeRequest::default()));
-        server.register_message(|| Box::new(FindNodeResponse::default()));
+impl TryFrom<&str> for Kademlia {
 
-        server.register_request_listener("ping", move |event| {
-            //println!("{}", event.get_message().to_string());
+    type Error = io::Error;
 
-            let mut response = PingResponse::new(*event.get_message().get_transaction_id());
-            response.set_destination(event.get_message().get_origin().unwrap());
-            response.set_public(event.get_message().get_origin().unwrap());
-            event.set_response(Box::new(response));
-        });
+    fn try_from(value: &str) -> io::Result<Self> {
+        let bucket_type = BucketTypes::from_string(value).ok_or_else(|| io::ErrorKind::InvalidData)?;
+        Ok(Self::from(bucket_type))
+    }
+}
 
-        let _self = Self {
-            routing_table: bucket_type.routing_table(),
-            server: Arc::new(Mutex::new(server)),
-            refresh: Arc::new(Mutex::new(RefreshHandler::new()))
+impl Kademlia {
+
+    pub fn get_peer_store(&self) -> &Arc<Mutex<PeerStore>> {
+        &self.peer_store
+    }
+
+    pub fn get_item_store(&self) -> &Arc<Mutex<ItemStore>> {
+        &self.item_store
+    }
+
+    pub fn get_token_manager(&self) -> &Arc<Mutex<TokenManager>> {
+        &self.token_manager
+    }
+
+    pub fn put_immutable<V: ToBencode>(&self, value: &V) -> io::Result<UID> {
+        let value = value.to_bencode();
+
+        if value.len() > MAX_VALUE_SIZE {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Value exceeds the maximum item size"));
+        }
+
+        let target = UID::from(sha1(&value));
+
+        let mut request = PutRequest::default();
+        request.set_value(&value);
+
+        let routing_table = self.get_lookup_routing_table().clone();
+        let handler = PutItemLookupHandler::new(self, routing_table.clone(), target, request);
+        IterativeLookup::new(self, routing_table, target, Arc::new(handler)).start()?;
+
+        Ok(target)
+    }
+
+    pub fn put_mutable<V: ToBencode>(&self, key_pair: &KeyPair, salt: Option<&[u8]>, seq: i64, cas: Option<i64>, value: &V) -> io::Result<UID> {
+        let value = value.to_bencode();
+
+        if value.len() > MAX_VALUE_SIZE {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Value exceeds the maximum item size"));
+        }
+
+        let salt = salt.filter(|salt| !salt.is_empty());
+
+        if salt.is_some_and(|salt| salt.len() > MAX_SALT_SIZE) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Salt exceeds the maximum size"));
+        }
+
+        let target = mutable_target(key_pair.get_public_key(), salt);
+
+        let mut request = PutRequest::default();
+        request.set_value(&value);
+        request.set_key(*key_pair.get_public_key());
+        request.set_seq(seq);
+        request.set_signature(key_pair.sign(&signature_buffer(salt, seq, &value)));
+
+        if let Some(salt) = salt {
+            request.set_salt(salt);
+        }
+
+        if let Some(cas) = cas {
+            request.set_cas(cas);
+        }
+
+        let routing_table = self.get_lookup_routing_table().clone();
+        let handler = PutItemLookupHandler::new(self, routing_table.clone(), target, request);
+        IterativeLookup::new(self, routing_table, target, Arc::new(handler)).start()?;
+
+        Ok(target)
+    }
+
+    pub fn get_immutable<F>(&self, target: UID, callback: F) -> io::Result<()>
+    where
+        F: Fn(Option<StoredItem>) + Send + Sync + 'static
+    {
+        let routing_table = self.get_lookup_routing_table().clone();
+        let handler = GetItemLookupHandler::new(routing_table.clone(), target, None, Arc::new(callback));
+        IterativeLookup::new(self, routing_table, target, Arc::new(handler)).start()
+    }
+
+    pub fn get_mutable<F>(&self, key: &[u8; PUBLIC_KEY_LENGTH], salt: Option<&[u8]>, callback: F) -> io::Result<()>
+    where
+        F: Fn(Option<StoredItem>) + Send + Sync + 'static
+    {
+        let salt = salt.filter(|salt| !salt.is_empty());
+        let target = mutable_target(key, salt);
+        let routing_table = self.get_lookup_routing_table().clone();
+        let handler = GetItemLookupHandler::new(routing_table.clone(), target, salt.map(|salt| salt.to_vec()), Arc::new(callback));
+        IterativeLookup::new(self, routing_table, target, Arc::new(handler)).start()
+    }
+
+    pub fn sample_infohashes<F>(&self, callback: F) -> io::Result<()>
+    where
+        F: Fn(Node, &SampleInfohashesResponse) + Send + Sync + 'static
+    {
+        SampleInfohashesWalk::new(self, self.get_lookup_routing_table().clone(), self.sampled.clone(), Arc::new(callback)).start()
+    }
+
+    //BEP 33, RESOLVES WITH THE SEED AND PEER FILTERS OF EVERY NODE THE LOOKUP REACHED MERGED TOGETHER
+    pub fn scrape(&self, info_hash: UID) -> QueryFuture<ScrapeEstimate> {
+        let (future, promise) = query_channel();
+
+        let mut handler = GetPeersLookupHandler::new(self.get_lookup_routing_table().clone(), info_hash, Arc::new({
+            let promise = promise.clone();
+            move |result: GetPeersResult| promise.complete(Ok(*result.get_scrape()))
+        }));
+        handler.set_scrape(true);
+
+        if let Err(e) = self.get_peers_lookup(handler) {
+            promise.complete(Err(e.into()));
+        }
+
+        future
+    }
+
+    pub fn save_state(&self) -> io::Result<()> {
+        match self.config.get_state_file() {
+            Some(state_file) => RoutingState::from_routing_tables(&self.routing_table, &self.routing_table6).save(state_file),
+            None => Ok(())
+        }
+    }
+
+    //LOADED BEFORE THE SERVER IS STARTED SO A BROKEN STATE FILE FAILS THE BIND INSTEAD OF LEAVING A HALF STARTED NODE
+    fn load_state(&self) -> io::Result<Option<RoutingState>> {
+        match self.config.get_state_file() {
+            Some(state_file) if state_file.exists() => RoutingState::load(state_file).map(Some),
+            _ => Ok(None)
+        }
+    }
+
+    //RESTORED NODES ARE ONLY PINGED, THEY MAKE IT INTO THE ROUTING TABLE ONCE THEY ANSWER
+    fn restore_state(&self, state: Option<RoutingState>) {
+        let state = match state {
+            Some(state) => state,
+            None => return
         };
 
-        _self.routing_table.lock().unwrap().add_restart_listener(Arc::new({
-            let _self = _self.clone();
-            move || {
-                let uid = _self.routing_table.lock().unwrap().get_derived_uid();
-                let closest = _self.routing_table.lock().unwrap().find_closest(&uid, MAX_BUCKET_SIZE);
+        if let Some(uid) = state.get_uid() {
+            self.restore_uid(uid);
+        }
 
-                if closest.is_empty() {
-                    return;
-                }
+        //A RESTORED NODE THAT CANT BE PINGED IS LEFT OUT THE SAME AS ONE THAT DOESNT ANSWER
+        let nodes = state.get_nodes().iter().chain(state.get_nodes6()).copied().collect();
+        self.ping_candidates(nodes, Vec::new()).ok();
+    }
+
+    //A CONFIGURED SEED OR EXPLICIT ID TAKES PRIORITY OVER ONE FROM A STATE FILE, BOTH ROUTING TABLES TAKE THE SAME ID
+    fn restore_uid(&self, uid: UID) {
+        for routing_table in [&self.routing_table, &self.routing_table6] {
+            let mut routing_table = routing_table.lock().unwrap();
+
+            if routing_table.get_uid_seed().is_none() && routing_table.get_explicit_uid().is_none() {
+                routing_table.set_derived_uid(uid);
+            }
+        }
+    }
+
+    //ONLY ALLOWED WHILE secure_only IS OFF, BOTH ROUTING TABLES TAKE THE SAME ID
+    pub fn set_uid(&self, uid: UID) -> io::Result<()> {
+        self.routing_table.lock().unwrap().set_explicit_uid(uid)?;
+        self.routing_table6.lock().unwrap().set_explicit_uid(uid)
+    }
+
+    //THE SERVER HAS TO BE BOUND FIRST, RETURNS HOW MANY OF THE IMPORTED NODES WERE PINGED OR THE FIRST
+    //PING THAT COULDNT BE SENT, THE REST OF THE NODES ARE PINGED EITHER WAY
+    pub fn import_libtorrent_state<P: AsRef<Path>>(&self, path: P) -> io::Result<usize> {
+        self.import_state(ImportedState::load_libtorrent(path)?)
+    }
 
-                for n in closest {
-                    let mut request = FindNodeRequest::default();
-                    request.set_destination(n.address);
-                    request.set_target(_self.routing_table.lock().unwrap().get_derived_uid());
+    pub fn import_transmission_state<P: AsRef<Path>>(&self, path: P) -> io::Result<usize> {
+        self.import_state(ImportedState::load_transmission(path)?)
+    }
+
+    fn import_state(&self, state: ImportedState) -> io::Result<usize> {
+        if !self.server.lock().unwrap().is_running() {
+            return Err(io::Error::new(io::ErrorKind::NotConnected, "Server is not running"));
+        }
+
+        if let Some(uid) = state.get_uid() {
+            self.restore_uid(uid);
+        }
+
+        self.ping_candidates(Vec::new(), state.get_addresses().clone())
+    }
+
+    //NODES WITH A KNOWN ID ARE HELD TO IT, BARE ADDRESSES LEARN THEIR ID FROM THE RESPONSE AND THE
+    //ROUTING TABLE CHECKS IT AGAINST secure_only ON INSERT
+    fn ping_candidates(&self, nodes: Vec<Node>, addresses: Vec<SocketAddr>) -> io::Result<usize> {
+        let (has_ipv4, has_ipv6) = {
+            let server = self.server.lock().unwrap();
+            (server.has_ipv4(), server.has_ipv6())
+        };
+
+        let mut pinged = 0;
+        let mut error = None;
+
+        for (address, node) in nodes.into_iter().map(|n| (n.address, Some(n))).chain(addresses.into_iter().map(|a| (a, None))) {
+            if (address.is_ipv4() && !has_ipv4) || (address.is_ipv6() && !has_ipv6) {
+                continue;
+            }
+
+            let routing_table = self.get_routing_table_for(&address).clone();
+
+            if routing_table.lock().unwrap().is_secure_only() && node.is_some_and(|n| !n.has_secure_id()) {
+                continue;
+            }
+
+            let mut request = PingRequest::default();
+            request.set_destination(address);
+            let listener = Box::new(PingResponseListener::new(routing_table));
+
+            let sent = match node {
+                Some(node) => self.server.lock().unwrap().send_with_node_callback(&mut request, node, listener),
+                None => self.server.lock().unwrap().send_with_callback(&mut request, listener)
+            };
 
-                    _self.server.lock().unwrap().send_with_callback(&mut request, Box::new(JoinNodeResponseListener::new(&_self))).unwrap();
+            match sent {
+                Ok(_) => pinged += 1,
+                Err(e) => {
+                    error.get_or_insert(e);
                 }
             }
-        }));
+        }
 
-        _self.refresh.lock().unwrap().add_operation(Box::new(BucketRefreshTask::new(&_self)));
-        _self.refresh.lock().unwrap().add_operation(Box::new(StaleRefreshTask::new(&_self)));
+        if pinged > 0 {
+            self.refresh.lock().unwrap().resume();
+        }
 
-        _self.server.lock().unwrap().register_request_listener("find_node", {
-            let _self = _self.clone();
+        match error {
+            Some(e) => Err(e),
+            None => Ok(pinged)
+        }
+    }
+
+    fn bootstrap(&self, addr: SocketAddr) -> io::Result<()> {
+        let mut request = FindNodeRequest::default();
+        request.set_destination(addr);
+        request.set_target(self.get_routing_table_for(&addr).lock().unwrap().get_derived_uid());
+
+        //ASK FOR BOTH FAMILIES SO THE OTHER ROUTING TABLE GETS SEEDED FROM THE SAME BOOTSTRAP
+        if self.server.lock().unwrap().is_dual_stack() {
+            request.set_want(vec![AddressTypes::Ipv4, AddressTypes::Ipv6]);
+        }
+
+        self.server.lock().unwrap().send_with_callback(&mut request, Box::new(JoinNodeResponseListener::new(self)))
+    }
+
+    //LOOKUPS RUN ON THE TABLE OF THE BOUND FAMILY, A DUAL STACK NODE USES IPV6 WHILE ITS IPV4 TABLE IS EMPTY
+    fn get_lookup_routing_table(&self) -> &Arc<Mutex<dyn RoutingTable>> {
+        let (has_ipv4, has_ipv6) = {
+            let server = self.server.lock().unwrap();
+            (server.has_ipv4(), server.has_ipv6())
+        };
+
+        if has_ipv6 && (!has_ipv4 || self.routing_table.lock().unwrap().all_nodes().is_empty()) {
+            return &self.routing_table6;
+        }
+
+        &self.routing_table
+    }
+
+    fn get_peers_lookup(&self, mut handler: GetPeersLookupHandler) -> io::Result<()> {
+        if self.server.lock().unwrap().is_dual_stack() {
+            handler.set_want(vec![AddressTypes::Ipv4, AddressTypes::Ipv6]);
+        }
+
+        IterativeLookup::new(self, handler.get_routing_table().clone(), handler.get_info_hash(), Arc::new(handler)).start()
+    }
+
+    //WITHOUT A WANT THE NODES COME FROM THE TABLE MATCHING THE REQUESTERS ADDRESS FAMILY
+    fn find_closest_wanted(&self, target: &UID, origin: &SocketAddr, want: &[AddressTypes]) -> Vec<Node> {
+        if want.is_empty() {
+            return self.get_routing_table_for(origin).lock().unwrap().find_closest(target, self.config.get_k());
+        }
+
+        let mut nodes = Vec::new();
+
+        for address_type in want {
+            let routing_table = match address_type {
+                AddressTypes::Ipv4 => &self.routing_table,
+                AddressTypes::Ipv6 => &self.routing_table6
+            };
+
+            nodes.extend(routing_table.lock().unwrap().find_closest(target, self.config.get_k()));
+        }
+
+        nodes
+    }
+
+    fn register_peer_listeners(&self) {
+        self.server.lock().unwrap().register_request_listener("get_peers", {
+            let _self = self.clone();
             move |event| {
-                //println!("{}", event.get_message().to_string());
                 if event.is_prevent_default() {
                     return;
                 }
 
-                let request = event.get_message().as_any().downcast_ref::<FindNodeRequest>().unwrap();
+                let request = event.get_message().as_any().downcast_ref::<GetPeersRequest>().unwrap();
+                let info_hash = request.get_info_hash().unwrap();
 
-                let mut nodes = _self.get_routing_table().lock().unwrap()
-                    .find_closest(&request.get_target().unwrap(), MAX_BUCKET_SIZE);
-                nodes.retain(|&n| n != event.get_node());
-
-                let mut response = FindNodeResponse::new(*event.get_message().get_transaction_id());
+                let mut response = GetPeersResponse::new(event.get_message().get_transaction_id());
                 response.set_destination(event.get_message().get_origin().unwrap());
                 response.set_public(event.get_message().get_origin().unwrap());
-                response.add_nodes(nodes);
-                event.set_response(Box::new(response));
-            }
-        });
+                response.set_token(&_self.token_manager.lock().unwrap().generate_token(event.get_message().get_origin().unwrap().ip()));
 
-        _self.server.lock().unwrap().kademlia = Some(_self.clone_dyn());
+                let peers = {
+                    let peer_store = _self.peer_store.lock().unwrap();
 
-        _self
-    }
-}
+                    if request.is_scrape() && peer_store.contains(&info_hash) {
+                        let (seeds, peers) = peer_store.get_scrape(&info_hash);
+                        response.set_seeds_filter(seeds);
+                        response.set_peers_filter(peers);
+                    }
 
-impl TryFrom<&str> for Kademlia {
-    
-    type Error = io::Error;
+                    peer_store.get_peers(&info_hash, MAX_VALUES, request.is_no_seed())
+                };
 
-    fn try_from(value: &str) -> io::Result<Self> {
-        let mut server = Server::new();
+                if peers.is_empty() {
+                    let mut nodes = _self.find_closest_wanted(&info_hash, &event.get_message().get_origin().unwrap(), request.get_want());
+                    nodes.retain(|&n| n != event.get_node());
+                    response.add_nodes(nodes);
 
-        server.register_message(|| Box::new(PingRequest::default()));
-        server.register_message(|| Box::new(PingResponse::default()));
-        server.register_message(|| Box::new(FindNodeRequest::default()));
-        server.register_message(|| Box::new(FindNodeResponse::default()));
-
-        server.register_request_listener("ping", move |event| {
-            //println!("{}", event.get_message().to_string());
+                } else {
+                    response.add_peers(peers);
+                }
 
-            let mut response = PingResponse::new(*event.get_message().get_transaction_id());
-            response.set_destination(event.get_message().get_origin().unwrap());
-            response.set_public(event.get_message().get_origin().unwrap());
-            event.set_response(Box::new(response));
+                event.set_response(Box::new(response));
+            }
         });
 
-        let _self = Self {
-            routing_table: BucketTypes::from_string(value).ok_or_else(|| io::ErrorKind::InvalidData)?.routing_table(),
-            server: Arc::new(Mutex::new(server)),
-            refresh: Arc::new(Mutex::new(RefreshHandler::new()))
-        };
+        self.server.lock().unwrap().register_request_listener("announce_peer", {
+            let _self = self.clone();
+            move |event| {
+                if event.is_prevent_default() {
+                    return;
+                }
 
-        _self.routing_table.lock().unwrap().add_restart_listener(Arc::new({
-            let _self = _self.clone();
-            move || {
-                let uid = _self.routing_table.lock().unwrap().get_derived_uid();
-                let closest = _self.routing_table.lock().unwrap().find_closest(&uid, MAX_BUCKET_SIZE);
+                let request = event.get_message().as_any().downcast_ref::<AnnouncePeerRequest>().unwrap();
+                let origin = event.get_message().get_origin().unwrap();
 
-                if closest.is_empty() {
+                if !_self.token_manager.lock().unwrap().validate_token(request.get_token().unwrap(), origin.ip()) {
+                    event.set_exception(MessageException::new("Protocol Error, invalid token.", 203));
                     return;
                 }
 
-                for n in closest {
-                    let mut request = FindNodeRequest::default();
-                    request.set_destination(n.address);
-                    request.set_target(_self.routing_table.lock().unwrap().get_derived_uid());
+                let port = if request.is_implied_port() {
+                    origin.port()
+                } else {
+                    request.get_port()
+                };
 
-                    _self.server.lock().unwrap().send_with_callback(&mut request, Box::new(JoinNodeResponseListener::new(&_self))).unwrap();
-                }
-            }
-        }));
+                _self.peer_store.lock().unwrap().add_peer(request.get_info_hash().unwrap(), SocketAddr::new(origin.ip(), port), request.is_seed());
 
-        _self.refresh.lock().unwrap().add_operation(Box::new(BucketRefreshTask::new(&_self)));
-        _self.refresh.lock().unwrap().add_operation(Box::new(StaleRefreshTask::new(&_self)));
+                let mut response = AnnouncePeerResponse::new(event.get_message().get_transaction_id());
+                response.set_destination(origin);
+                response.set_public(origin);
+                event.set_response(Box::new(response));
+            }
+        });
 
-        _self.server.lock().unwrap().register_request_listener("find_node", {
-            let _self = _self.clone();
+        self.server.lock().unwrap().register_request_listener("sample_infohashes", {
+            let _self = self.clone();
             move |event| {
-                //println!("{}", event.get_message().to_string());
                 if event.is_prevent_default() {
                     return;
                 }
 
-                let request = event.get_message().as_any().downcast_ref::<FindNodeRequest>().unwrap();
+                let request = event.get_message().as_any().downcast_ref::<SampleInfohashesRequest>().unwrap();
+                let target = request.get_target().unwrap();
+
+                let mut response = SampleInfohashesResponse::new(event.get_message().get_transaction_id());
+                response.set_destination(event.get_message().get_origin().unwrap());
+                response.set_public(event.get_message().get_origin().unwrap());
 
-                let mut nodes = _self.get_routing_table().lock().unwrap()
-                    .find_closest(&request.get_target().unwrap(), MAX_BUCKET_SIZE);
+                {
+                    let mut peer_store = _self.peer_store.lock().unwrap();
+                    response.add_samples(peer_store.sample());
+                    response.set_interval(peer_store.get_sample_interval());
+                    response.set_num(peer_store.len() as u32);
+                }
+
+                let mut nodes = _self.find_closest_wanted(&target, &event.get_message().get_origin().unwrap(), &[]);
                 nodes.retain(|&n| n != event.get_node());
+                response.add_nodes(nodes);
 
-                let mut response = FindNodeResponse::new(*event.get_message().get_transaction_id());
+                event.set_response(Box::new(response));
+            }
+        });
+    }
+
+    fn register_item_listeners(&self) {
+        self.server.lock().unwrap().register_request_listener("get", {
+            let _self = self.clone();
+            move |event| {
+                if event.is_prevent_default() {
+                    return;
+                }
+
+                let request = event.get_message().as_any().downcast_ref::<GetRequest>().unwrap();
+                let target = request.get_target().unwrap();
+
+                let mut response = GetResponse::new(event.get_message().get_transaction_id());
                 response.set_destination(event.get_message().get_origin().unwrap());
                 response.set_public(event.get_message().get_origin().unwrap());
+                response.set_token(&_self.token_manager.lock().unwrap().generate_token(event.get_message().get_origin().unwrap().ip()));
+
+                if let Some(item) = _self.item_store.lock().unwrap().get(&target) {
+                    match item.get_key() {
+                        Some(key) => {
+                            response.set_seq(item.get_seq());
+
+                            //SKIP THE VALUE WHEN THE REQUESTER ALREADY HAS THIS SEQ OR NEWER
+                            if request.get_seq().is_none_or(|seq| seq < item.get_seq()) {
+                                response.set_key(*key);
+                                response.set_signature(*item.get_signature().unwrap());
+                                response.set_value(item.get_value());
+                            }
+                        }
+                        None => response.set_value(item.get_value())
+                    }
+                }
+
+                let mut nodes = _self.find_closest_wanted(&target, &event.get_message().get_origin().unwrap(), &[]);
+                nodes.retain(|&n| n != event.get_node());
                 response.add_nodes(nodes);
+
                 event.set_response(Box::new(response));
             }
         });
 
-        _self.server.lock().unwrap().kademlia = Some(_self.clone_dyn());
+        self.server.lock().unwrap().register_request_listener("put", {
+            let _self = self.clone();
+            move |event| {
+                if event.is_prevent_default() {
+                    return;
+                }
 
-        Ok(_self)
+                let request = event.get_message().as_any().downcast_ref::<PutRequest>().unwrap();
+                let origin = event.get_message().get_origin().unwrap();
+
+                if !_self.token_manager.lock().unwrap().validate_token(request.get_token().unwrap(), origin.ip()) {
+                    event.set_exception(MessageException::new("Protocol Error, invalid token.", 203));
+                    return;
+                }
+
+                let value = request.get_value().unwrap();
+
+                if value.len() > MAX_VALUE_SIZE {
+                    event.set_exception(MessageException::new("Message (v field) too big.", 205));
+                    return;
+                }
+
+                match request.get_key() {
+                    Some(key) => {
+                        let salt = request.get_salt().filter(|salt| !salt.is_empty());
+
+                        if salt.is_some_and(|salt| salt.len() > MAX_SALT_SIZE) {
+                            event.set_exception(MessageException::new("Salt (salt field) too big.", 207));
+                            return;
+                        }
+
+                        let seq = request.get_seq().unwrap();
+                        let signature = request.get_signature().unwrap();
+
+                        if !verify(key, &signature_buffer(salt.map(|s| s.as_slice()), seq, value), signature) {
+                            event.set_exception(MessageException::new("Invalid signature.", 206));
+                            return;
+                        }
+
+                        let target = mutable_target(key, salt.map(|s| s.as_slice()));
+                        let mut item_store = _self.item_store.lock().unwrap();
+
+                        if let Some(item) = item_store.get(&target) {
+                            if request.get_cas().is_some_and(|cas| cas != item.get_seq()) {
+                                event.set_exception(MessageException::new("The CAS hash mismatched, re-read value and try again.", 301));
+                                return;
+                            }
+
+                            if seq < item.get_seq() || (seq == item.get_seq() && value != item.get_value()) {
+                                event.set_exception(MessageException::new("Sequence number less than current.", 302));
+                                return;
+                            }
+                        }
+
+                        item_store.put_mutable(target, *key, salt.cloned(), *signature, seq, value.clone());
+                    }
+                    None => _self.item_store.lock().unwrap().put(UID::from(sha1(value)), value.clone())
+                }
+
+                let mut response = PutResponse::new(event.get_message().get_transaction_id());
+                response.set_destination(origin);
+                response.set_public(origin);
+                event.set_response(Box::new(response));
+            }
+        });
+    }
+}
+
+//ASYNC VARIANTS OF THE QUERY API, THE RESPONSE CALLBACKS WAKE THE AWAITING TASK
+#[cfg(feature = "tokio")]
+impl Kademlia {
+
+    pub async fn ping_async(&self, address: SocketAddr) -> Result<PingResponse, QueryError> {
+        self.ping(address).await
+    }
+
+    pub async fn find_node_async(&self, target: UID) -> Result<Vec<Node>, QueryError> {
+        self.find_node(target).await
+    }
+
+    pub async fn get_peers_async(&self, info_hash: UID) -> Result<GetPeersResult, QueryError> {
+        self.get_peers(info_hash).await
+    }
+
+    pub async fn announce_async(&self, info_hash: UID, port: u16) -> Result<Vec<Node>, QueryError> {
+        self.announce(info_hash, port).await
+    }
+
+    pub async fn scrape_async(&self, info_hash: UID) -> Result<ScrapeEstimate, QueryError> {
+        self.scrape(info_hash).await
     }
 }
 
 impl KademliaBase for Kademlia {
 
     fn bind(&self, port: u16) -> io::Result<()> {
-        self.server.lock().unwrap().start(port)
+        let state = self.load_state()?;
+        self.server.lock().unwrap().start(port)?;
+        self.refresh.lock().unwrap().reset();
+        self.restore_state(state);
+        Ok(())
+    }
+
+    fn bind_to(&self, address: SocketAddr) -> io::Result<()> {
+        let state = self.load_state()?;
+        self.server.lock().unwrap().start_on(address)?;
+        self.refresh.lock().unwrap().reset();
+        self.restore_state(state);
+        Ok(())
     }
 
     fn join(&self, local_port: u16, addr: SocketAddr) -> io::Result<()> {
+        let state = self.load_state()?;
         self.server.lock().unwrap().start(local_port)?;
+        self.refresh.lock().unwrap().reset();
+        self.restore_state(state);
+        self.bootstrap(addr)
+    }
 
-        let mut request = FindNodeRequest::default();
-        request.set_destination(addr);
-        request.set_target(self.routing_table.lock().unwrap().get_derived_uid());
-
-        self.server.lock().unwrap().send_with_callback(&mut request, Box::new(JoinNodeResponseListener::new(self)))
+    fn join_from(&self, local_address: SocketAddr, addr: SocketAddr) -> io::Result<()> {
+        let state = self.load_state()?;
+        self.server.lock().unwrap().start_on(local_address)?;
+        self.refresh.lock().unwrap().reset();
+        self.restore_state(state);
+        self.bootstrap(addr)
     }
 
-    fn stop(&self) {
+    fn stop(&self) -> io::Result<()> {
         self.server.lock().unwrap().stop();
         self.refresh.lock().unwrap().stop();
+        self.save_state()
+    }
+
+    fn ping(&self, address: SocketAddr) -> QueryFuture<PingResponse> {
+        let (future, promise) = query_channel();
+
+        let mut request = PingRequest::default();
+        request.set_destination(address);
+
+        let listener = QueryResponseListener::new(self.get_routing_table_for(&address).clone(), promise.clone());
+
+        if let Err(e) = self.server.lock().unwrap().send_with_callback(&mut request, Box::new(listener)) {
+            promise.complete(Err(e.into()));
+        }
+
+        future
+    }
+
+    fn find_node(&self, target: UID) -> QueryFuture<Vec<Node>> {
+        let (future, promise) = query_channel();
+
+        let routing_table = self.get_lookup_routing_table().clone();
+        let mut handler = FindNodeLookupHandler::new(routing_table.clone(), target);
+        handler.set_callback(Arc::new({
+            let promise = promise.clone();
+            move |closest| promise.complete(Ok(closest))
+        }));
+
+        if let Err(e) = IterativeLookup::new(self, routing_table, target, Arc::new(handler)).start() {
+            promise.complete(Err(e.into()));
+        }
+
+        future
+    }
+
+    fn get_peers(&self, info_hash: UID) -> QueryFuture<GetPeersResult> {
+        let (future, promise) = query_channel();
+
+        let handler = GetPeersLookupHandler::new(self.get_lookup_routing_table().clone(), info_hash, Arc::new({
+            let promise = promise.clone();
+            move |result| promise.complete(Ok(result))
+        }));
+
+        if let Err(e) = self.get_peers_lookup(handler) {
+            promise.complete(Err(e.into()));
+        }
+
+        future
+    }
+
+    fn announce(&self, info_hash: UID, port: u16) -> QueryFuture<Vec<Node>> {
+        let (future, promise) = query_channel();
+
+        //ANNOUNCING NEEDS THE WRITE TOKENS OF THE CLOSEST NODES, SO A GET_PEERS LOOKUP ALWAYS COMES FIRST
+        let routing_table = self.get_lookup_routing_table().clone();
+        let handler = GetPeersLookupHandler::new(routing_table.clone(), info_hash, Arc::new({
+            let _self = self.clone();
+            let promise = promise.clone();
+            move |result: GetPeersResult| {
+                if result.get_tokens().is_empty() {
+                    promise.complete(Ok(Vec::new()));
+                    return;
+                }
+
+                let listener = AnnounceResponseListener::new(routing_table.clone(), promise.clone(), result.get_tokens().len());
+
+                for (node, token) in result.get_tokens() {
+                    let mut request = AnnouncePeerRequest::default();
+                    request.set_destination(node.address);
+                    request.set_info_hash(info_hash);
+                    request.set_port(port);
+                    request.set_token(token);
+
+                    //A QUERY THAT CANT BE SENT COUNTS AS ONE THAT WASNT ACCEPTED
+                    if _self.server.lock().unwrap().send_with_node_callback(&mut request, *node, Box::new(listener.clone())).is_err() {
+                        listener.complete(None);
+                    }
+                }
+            }
+        }));
+
+        if let Err(e) = self.get_peers_lookup(handler) {
+            promise.complete(Err(e.into()));
+        }
+
+        future
     }
 
     fn get_server(&self) -> &Arc<Mutex<Server>> {
@@ -285,14 +885,31 @@ impl KademliaBase for Kademlia {
         &self.routing_table
     }
 
+    fn get_routing_table6(&self) -> &Arc<Mutex<dyn RoutingTable>> {
+        &self.routing_table6
+    }
+
     fn get_refresh_handler(&self) -> &Arc<Mutex<RefreshHandler>> {
         &self.refresh
     }
 
+    fn get_config(&self) -> &Config {
+        &self.config
+    }
+
     fn join_thread(&self) {
         if self.server.lock().unwrap().is_running() {
             let handle = self.server.lock().as_mut().unwrap().handle.take().unwrap();
+
+            #[cfg(not(feature = "tokio"))]
             handle.join().unwrap();
+
+            //THE TASK IS AWAITED FROM A PLAIN THREAD SO THIS ALSO WORKS WHEN CALLED FROM A RUNTIME WORKER
+            #[cfg(feature = "tokio")]
+            {
+                let runtime = tokio::runtime::Handle::current();
+                std::thread::spawn(move || runtime.block_on(handle)).join().unwrap().unwrap();
+            }
         }
     }
 
@@ -300,3 +917,119 @@ impl KademliaBase for Kademlia {
         Box::new(self.clone())
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use std::net::IpAddr;
+    use std::thread::sleep;
+    use std::time::Duration;
+    use rlibbencode::variables::bencode_object::{BencodeObject, PutObject};
+    use crate::kad::kademlia_builder::KademliaBuilder;
+    use crate::utils::uid::ID_LENGTH;
+    use super::*;
+
+    #[test]
+    fn scrape_lookup() {
+        let kademlia = KademliaBuilder::new().secure_only(false).allow_bogon(true).build().unwrap();
+        let bootstrap = KademliaBuilder::new().secure_only(false).allow_bogon(true).build().unwrap();
+        bootstrap.bind(19401).unwrap();
+        kademlia.join(19402, SocketAddr::new(IpAddr::from([127, 0, 0, 1]), 19401)).unwrap();
+        sleep(Duration::from_millis(500));
+
+        let info_hash = UID::from([3u8; ID_LENGTH]);
+        for i in 0..40u8 {
+            bootstrap.get_peer_store().lock().unwrap().add_peer(info_hash, SocketAddr::new(IpAddr::from([10, 0, 0, i]), 6881), i < 10);
+        }
+
+        let scrape = kademlia.scrape(info_hash).wait_timeout(Duration::from_secs(10));
+        kademlia.stop().unwrap();
+        bootstrap.stop().unwrap();
+
+        let scrape = scrape.unwrap();
+        assert!((9..=11).contains(&scrape.get_seeds()));
+        assert!((28..=32).contains(&scrape.get_peers()));
+    }
+
+    #[test]
+    fn ipv6_lookup() {
+        let kademlia = KademliaBuilder::new().secure_only(false).allow_bogon(true).build().unwrap();
+        let bootstrap = KademliaBuilder::new().secure_only(false).allow_bogon(true).build().unwrap();
+        let bootstrap_address = SocketAddr::new(IpAddr::from([0, 0, 0, 0, 0, 0, 0, 1]), 19403);
+        bootstrap.bind_to(bootstrap_address).unwrap();
+        kademlia.join_from(SocketAddr::new(IpAddr::from([0, 0, 0, 0, 0, 0, 0, 1]), 19404), bootstrap_address).unwrap();
+        sleep(Duration::from_millis(500));
+
+        //NOTHING WAS LEARNED OVER IPV4, THE LOOKUP HAS TO START FROM THE IPV6 TABLE
+        let closest = kademlia.find_node(UID::from([5u8; ID_LENGTH])).wait_timeout(Duration::from_secs(10));
+        kademlia.stop().unwrap();
+        bootstrap.stop().unwrap();
+
+        assert!(closest.unwrap().iter().any(|n| n.address == bootstrap_address));
+    }
+
+    #[test]
+    fn failed_announce_send() {
+        let kademlia = KademliaBuilder::new().secure_only(false).build().unwrap();
+        kademlia.bind(19405).unwrap();
+        kademlia.get_server().lock().unwrap().set_stalled_time(100);
+
+        //A SEND THAT FAILS IS COUNTED ONCE BY THE CALLER, IT MUST NOT STALL AND COUNT AGAIN
+        let (future, promise) = query_channel();
+        let listener = AnnounceResponseListener::new(kademlia.get_routing_table().clone(), promise, 2);
+        let node = Node::new(UID::from([6u8; ID_LENGTH]), SocketAddr::new(IpAddr::from([10, 0, 0, 1]), 0));
+
+        let mut request = AnnouncePeerRequest::default();
+        request.set_destination(node.address);
+        assert!(kademlia.get_server().lock().unwrap().send_with_node_callback(&mut request, node, Box::new(listener.clone())).is_err());
+        listener.complete(None);
+
+        sleep(Duration::from_millis(2500));
+        kademlia.stop().unwrap();
+        assert!(!future.is_done());
+    }
+
+    #[test]
+    fn restore_uid() {
+        let state_file = std::env::temp_dir().join("rlibdht_restore_uid.dat");
+        let kademlia = KademliaBuilder::new().secure_only(false).state_file(&state_file).build().unwrap();
+        let uid = kademlia.get_routing_table().lock().unwrap().get_derived_uid();
+        kademlia.save_state().unwrap();
+
+        let restored = KademliaBuilder::new().secure_only(false).state_file(&state_file).build().unwrap();
+        restored.restore_state(restored.load_state().unwrap());
+        std::fs::remove_file(&state_file).ok();
+
+        assert!(restored.get_routing_table().lock().unwrap().get_derived_uid() == uid);
+        assert!(restored.get_routing_table6().lock().unwrap().get_derived_uid() == uid);
+    }
+    #[test]
+    fn broken_state_file() {
+        let state_file = std::env::temp_dir().join("rlibdht_broken_state_file.dat");
+        //AN ID OF THE WRONG LENGTH
+        std::fs::write(&state_file, b"d2:id3:abce").unwrap();
+
+        let kademlia = KademliaBuilder::new().secure_only(false).state_file(&state_file).build().unwrap();
+        assert!(kademlia.bind(19406).is_err());
+        std::fs::remove_file(&state_file).ok();
+
+        assert!(!kademlia.get_server().lock().unwrap().is_running());
+    }
+
+    #[test]
+    fn failed_import_send() {
+        let state_file = std::env::temp_dir().join("rlibdht_failed_import_send.dat");
+        let mut dat = BencodeObject::new();
+        dat.put("nodes", vec![127u8, 0, 0, 1, 0, 0]);
+        std::fs::write(&state_file, dat.to_bencode()).unwrap();
+
+        let kademlia = KademliaBuilder::new().secure_only(false).build().unwrap();
+        assert!(kademlia.import_transmission_state(&state_file).is_err());
+
+        //A PORT OF 0 CANT BE PINGED, THE ERROR IS RETURNED INSTEAD OF A COUNT
+        kademlia.bind(19407).unwrap();
+        assert!(kademlia.import_transmission_state(&state_file).is_err());
+        std::fs::remove_file(&state_file).ok();
+        kademlia.stop().unwrap();
+    }
+}
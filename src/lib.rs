@@ -5,6 +5,8 @@ pub mod kad;
 pub mod kademlia;
 pub mod refresh;
 pub mod rpc;
+pub mod storage;
+pub mod lookup;
 pub extern crate rlibbencode;
 
 //MAYBE MAKE ROUTING TABLE A BASE SET - IE ABSTRACT - NOT TRAIT
@@ -0,0 +1,66 @@
+use std::sync::{Arc, Mutex};
+use crate::lookup::inter::lookup_handler::LookupHandler;
+use crate::messages::find_node_request::FindNodeRequest;
+use crate::messages::find_node_response::FindNodeResponse;
+use crate::messages::inter::method_message_base::MethodMessageBase;
+use crate::routing::inter::routing_table::{insert_checked, RoutingTable};
+use crate::rpc::events::inter::message_event::MessageEvent;
+use crate::rpc::events::response_event::ResponseEvent;
+use crate::utils::node::Node;
+use crate::utils::uid::UID;
+
+pub type FindNodeCallback = Arc<dyn Fn(Vec<Node>) + Send + Sync>;
+
+//FIND_NODE LOOKUP, EVERY NODE THAT ANSWERS IS ADDED TO THE ROUTING TABLE OF ITS ADDRESS FAMILY
+pub struct FindNodeLookupHandler {
+    routing_table: Arc<Mutex<dyn RoutingTable>>,
+    target: UID,
+    callback: Option<FindNodeCallback>
+}
+
+impl FindNodeLookupHandler {
+
+    pub fn new(routing_table: Arc<Mutex<dyn RoutingTable>>, target: UID) -> Self {
+        Self {
+            routing_table,
+            target,
+            callback: None
+        }
+    }
+
+    pub fn set_callback(&mut self, callback: FindNodeCallback) {
+        self.callback = Some(callback);
+    }
+}
+
+impl LookupHandler for FindNodeLookupHandler {
+
+    fn create_request(&self, _node: &Node) -> Box<dyn MethodMessageBase> {
+        let mut request = FindNodeRequest::default();
+        request.set_target(self.target);
+        Box::new(request)
+    }
+
+    fn on_response(&self, event: &ResponseEvent) -> Vec<Node> {
+        let response = event.get_message().as_any().downcast_ref::<FindNodeResponse>().unwrap();
+        accept_nodes(&self.routing_table, event, response.get_all_nodes())
+    }
+
+    fn on_complete(&self, closest: Vec<Node>) {
+        if let Some(callback) = &self.callback {
+            callback(closest);
+        }
+    }
+}
+
+//THE NODE THAT ANSWERED GOES INTO THE ROUTING TABLE, ONLY NODES OF ITS ADDRESS FAMILY ARE FOLLOWED AND WITH
+//secure_only ON ONLY THE ONES WITH A BEP 42 ID
+pub fn accept_nodes(routing_table: &Arc<Mutex<dyn RoutingTable>>, event: &ResponseEvent, nodes: Vec<Node>) -> Vec<Node> {
+    insert_checked(routing_table, event.get_node());
+    let secure_only = routing_table.lock().unwrap().is_secure_only();
+
+    nodes.into_iter()
+        .filter(|node| node.address.is_ipv4() == event.get_node().address.is_ipv4())
+        .filter(|node| !secure_only || node.has_secure_id())
+        .collect()
+}
@@ -0,0 +1,117 @@
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::lookup::find_node_lookup_handler::accept_nodes;
+use crate::lookup::inter::lookup_handler::LookupHandler;
+use crate::messages::get_request::GetRequest;
+use crate::messages::get_response::GetResponse;
+use crate::messages::inter::method_message_base::MethodMessageBase;
+use crate::routing::inter::routing_table::RoutingTable;
+use crate::rpc::events::inter::message_event::MessageEvent;
+use crate::rpc::events::response_event::ResponseEvent;
+use crate::storage::item_store::{mutable_target, signature_buffer, StoredItem};
+use crate::utils::ed25519::verify;
+use crate::utils::hash::sha1::sha1;
+use crate::utils::node::Node;
+use crate::utils::uid::UID;
+
+pub type GetItemCallback = Arc<dyn Fn(Option<StoredItem>) + Send + Sync>;
+
+//GET LOOKUP, KEEPS THE VALID ITEM WITH THE HIGHEST SEQ SEEN FROM ANY NODE
+pub struct GetItemLookupHandler {
+    routing_table: Arc<Mutex<dyn RoutingTable>>,
+    target: UID,
+    salt: Option<Vec<u8>>,
+    best: Mutex<Option<StoredItem>>,
+    callback: GetItemCallback
+}
+
+impl GetItemLookupHandler {
+
+    pub fn new(routing_table: Arc<Mutex<dyn RoutingTable>>, target: UID, salt: Option<Vec<u8>>, callback: GetItemCallback) -> Self {
+        Self {
+            routing_table,
+            target,
+            salt,
+            best: Mutex::new(None),
+            callback
+        }
+    }
+
+    fn validate(&self, response: &GetResponse) -> Option<StoredItem> {
+        let value = response.get_value()?;
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_millis();
+
+        match response.get_key() {
+            Some(key) => {
+                let salt = self.salt.as_deref().filter(|salt| !salt.is_empty());
+                let seq = response.get_seq()?;
+                let signature = response.get_signature()?;
+
+                if mutable_target(key, salt) != self.target || !verify(key, &signature_buffer(salt, seq, value), signature) {
+                    return None;
+                }
+
+                Some(StoredItem {
+                    value: value.clone(),
+                    key: Some(*key),
+                    salt: salt.map(|salt| salt.to_vec()),
+                    signature: Some(*signature),
+                    seq,
+                    stored: now
+                })
+            }
+            None => {
+                if UID::from(sha1(value)) != self.target {
+                    return None;
+                }
+
+                Some(StoredItem {
+                    value: value.clone(),
+                    key: None,
+                    salt: None,
+                    signature: None,
+                    seq: 0,
+                    stored: now
+                })
+            }
+        }
+    }
+}
+
+impl LookupHandler for GetItemLookupHandler {
+
+    fn create_request(&self, _node: &Node) -> Box<dyn MethodMessageBase> {
+        let mut request = GetRequest::default();
+        request.set_target(self.target);
+
+        //NODES HOLDING AN OLDER OR EQUAL SEQ SKIP SENDING THE VALUE
+        if let Some(seq) = self.best.lock().unwrap().as_ref().filter(|item| item.is_mutable()).map(|item| item.get_seq()) {
+            request.set_seq(seq);
+        }
+
+        Box::new(request)
+    }
+
+    fn on_response(&self, event: &ResponseEvent) -> Vec<Node> {
+        let response = event.get_message().as_any().downcast_ref::<GetResponse>().unwrap();
+
+        if let Some(item) = self.validate(response) {
+            let mut best = self.best.lock().unwrap();
+
+            if best.as_ref().is_none_or(|best| item.get_seq() > best.get_seq()) {
+                *best = Some(item);
+            }
+        }
+
+        accept_nodes(&self.routing_table, event, response.get_all_nodes())
+    }
+
+    fn on_complete(&self, _closest: Vec<Node>) {
+        let best = self.best.lock().unwrap().take();
+        (self.callback)(best);
+    }
+}
@@ -0,0 +1,129 @@
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use crate::lookup::find_node_lookup_handler::accept_nodes;
+use crate::lookup::inter::lookup_handler::LookupHandler;
+use crate::messages::get_peers_request::GetPeersRequest;
+use crate::messages::get_peers_response::GetPeersResponse;
+use crate::messages::inter::method_message_base::MethodMessageBase;
+use crate::routing::inter::routing_table::RoutingTable;
+use crate::rpc::events::inter::message_event::MessageEvent;
+use crate::rpc::events::response_event::ResponseEvent;
+use crate::utils::bloom_filter::ScrapeEstimate;
+use crate::utils::net::address_types::AddressTypes;
+use crate::utils::node::Node;
+use crate::utils::uid::UID;
+
+pub type GetPeersCallback = Arc<dyn Fn(GetPeersResult) + Send + Sync>;
+
+#[derive(Clone, Default, Debug)]
+pub struct GetPeersResult {
+    peers: Vec<SocketAddr>,
+    tokens: Vec<(Node, Vec<u8>)>,
+    scrape: ScrapeEstimate
+}
+
+impl GetPeersResult {
+
+    pub fn get_peers(&self) -> &Vec<SocketAddr> {
+        &self.peers
+    }
+
+    //WRITE TOKENS OF THE CLOSEST NODES THAT ANSWERED, IN ORDER OF DISTANCE, NEEDED TO ANNOUNCE
+    pub fn get_tokens(&self) -> &Vec<(Node, Vec<u8>)> {
+        &self.tokens
+    }
+
+    pub fn get_scrape(&self) -> &ScrapeEstimate {
+        &self.scrape
+    }
+}
+
+//GET_PEERS LOOKUP, COLLECTS PEERS, WRITE TOKENS AND SCRAPE FILTERS FROM EVERY RESPONSE
+pub struct GetPeersLookupHandler {
+    routing_table: Arc<Mutex<dyn RoutingTable>>,
+    info_hash: UID,
+    want: Vec<AddressTypes>,
+    scrape: bool,
+    no_seed: bool,
+    result: Mutex<GetPeersResult>,
+    callback: GetPeersCallback
+}
+
+impl GetPeersLookupHandler {
+
+    pub fn new(routing_table: Arc<Mutex<dyn RoutingTable>>, info_hash: UID, callback: GetPeersCallback) -> Self {
+        Self {
+            routing_table,
+            info_hash,
+            want: Vec::new(),
+            scrape: false,
+            no_seed: false,
+            result: Mutex::new(GetPeersResult::default()),
+            callback
+        }
+    }
+
+    pub fn get_routing_table(&self) -> &Arc<Mutex<dyn RoutingTable>> {
+        &self.routing_table
+    }
+
+    pub fn get_info_hash(&self) -> UID {
+        self.info_hash
+    }
+
+    pub fn set_want(&mut self, want: Vec<AddressTypes>) {
+        self.want = want;
+    }
+
+    pub fn set_scrape(&mut self, scrape: bool) {
+        self.scrape = scrape;
+    }
+
+    pub fn set_no_seed(&mut self, no_seed: bool) {
+        self.no_seed = no_seed;
+    }
+}
+
+impl LookupHandler for GetPeersLookupHandler {
+
+    fn create_request(&self, _node: &Node) -> Box<dyn MethodMessageBase> {
+        let mut request = GetPeersRequest::default();
+        request.set_info_hash(self.info_hash);
+        request.set_want(self.want.clone());
+        request.set_scrape(self.scrape);
+        request.set_no_seed(self.no_seed);
+        Box::new(request)
+    }
+
+    fn on_response(&self, event: &ResponseEvent) -> Vec<Node> {
+        let response = event.get_message().as_any().downcast_ref::<GetPeersResponse>().unwrap();
+        {
+            let mut result = self.result.lock().unwrap();
+
+            for peer in response.get_peers() {
+                if !result.peers.contains(&peer) {
+                    result.peers.push(peer);
+                }
+            }
+
+            if let Some(token) = response.get_token() {
+                result.tokens.push((event.get_node(), token.clone()));
+            }
+
+            result.scrape.merge(response.get_seeds_filter(), response.get_peers_filter());
+        }
+
+        accept_nodes(&self.routing_table, event, response.get_all_nodes())
+    }
+
+    fn on_complete(&self, closest: Vec<Node>) {
+        let mut result = self.result.lock().unwrap().clone();
+
+        //ONLY THE CLOSEST NODES SHOULD BE ANNOUNCED TO, KEEP THEIR TOKENS IN LOOKUP ORDER
+        result.tokens = closest.iter()
+            .filter_map(|node| result.tokens.iter().find(|(n, _)| n == node).cloned())
+            .collect();
+
+        (self.callback)(result);
+    }
+}
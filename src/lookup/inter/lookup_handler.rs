@@ -0,0 +1,12 @@
+use crate::messages::inter::method_message_base::MethodMessageBase;
+use crate::rpc::events::response_event::ResponseEvent;
+use crate::utils::node::Node;
+
+pub trait LookupHandler: Send + Sync {
+
+    fn create_request(&self, node: &Node) -> Box<dyn MethodMessageBase>;
+
+    fn on_response(&self, event: &ResponseEvent) -> Vec<Node>;
+
+    fn on_complete(&self, closest: Vec<Node>);
+}
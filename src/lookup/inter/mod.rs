@@ -0,0 +1 @@
+pub mod lookup_handler;
@@ -0,0 +1,248 @@
+use std::io;
+use std::sync::{Arc, Mutex};
+use crate::kad::kademlia_base::KademliaBase;
+use crate::lookup::inter::lookup_handler::LookupHandler;
+use crate::routing::inter::routing_table::RoutingTable;
+use crate::routing::kb::k_comparator::KComparator;
+use crate::rpc::events::error_response_event::ErrorResponseEvent;
+use crate::rpc::events::inter::message_event::MessageEvent;
+use crate::rpc::events::inter::response_callback::ResponseCallback;
+use crate::rpc::events::response_event::ResponseEvent;
+use crate::rpc::events::stalled_event::StalledEvent;
+use crate::utils::node::Node;
+use crate::utils::uid::UID;
+
+pub const DEFAULT_ALPHA: usize = 3;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum QueryState {
+    Pending,
+    InFlight,
+    Responded,
+    Failed
+}
+
+struct LookupState {
+    shortlist: Vec<(Node, QueryState)>,
+    in_flight: usize,
+    started: bool,
+    finished: bool
+}
+
+//ITERATIVE KADEMLIA LOOKUP, KEEPS AT MOST ALPHA QUERIES IN FLIGHT AND STOPS ONCE THE K CLOSEST NODES HAVE ALL ANSWERED
+#[derive(Clone)]
+pub struct IterativeLookup {
+    kademlia: Box<dyn KademliaBase>,
+    routing_table: Arc<Mutex<dyn RoutingTable>>,
+    target: UID,
+    alpha: usize,
+    k: usize,
+    handler: Arc<dyn LookupHandler>,
+    state: Arc<Mutex<LookupState>>
+}
+
+impl IterativeLookup {
+
+    pub fn new(kademlia: &dyn KademliaBase, routing_table: Arc<Mutex<dyn RoutingTable>>, target: UID, handler: Arc<dyn LookupHandler>) -> Self {
+        Self {
+            kademlia: kademlia.clone_dyn(),
+            routing_table,
+            target,
+            alpha: kademlia.get_config().get_alpha(),
+            k: kademlia.get_config().get_k(),
+            handler,
+            state: Arc::new(Mutex::new(LookupState {
+                shortlist: Vec::new(),
+                in_flight: 0,
+                started: false,
+                finished: false
+            }))
+        }
+    }
+
+    pub fn set_alpha(&mut self, alpha: usize) {
+        self.alpha = alpha.max(1);
+    }
+
+    pub fn get_alpha(&self) -> usize {
+        self.alpha
+    }
+
+    pub fn set_k(&mut self, k: usize) {
+        self.k = k.max(1);
+    }
+
+    pub fn get_k(&self) -> usize {
+        self.k
+    }
+
+    pub fn get_target(&self) -> UID {
+        self.target
+    }
+
+    pub fn add_nodes(&self, nodes: Vec<Node>) {
+        let mut state = self.state.lock().unwrap();
+        self.insert(&mut state, nodes);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.state.lock().unwrap().finished
+    }
+
+    pub fn start(&self) -> io::Result<()> {
+        let closest = self.routing_table.lock().unwrap().find_closest(&self.target, self.k);
+
+        {
+            let mut state = self.state.lock().unwrap();
+
+            if state.started {
+                return Err(io::Error::other("Lookup has already started"));
+            }
+
+            state.started = true;
+            self.insert(&mut state, closest);
+
+            if state.shortlist.is_empty() {
+                state.finished = true;
+                return Err(io::Error::new(io::ErrorKind::NotConnected, "No nodes to query"));
+            }
+        }
+
+        self.advance();
+        Ok(())
+    }
+
+    fn insert(&self, state: &mut LookupState, nodes: Vec<Node>) {
+        let uid = self.routing_table.lock().unwrap().get_derived_uid();
+        let comparator = KComparator::new(&self.target);
+
+        for node in nodes {
+            if uid == node.uid || state.shortlist.iter().any(|(n, _)| *n == node) {
+                continue;
+            }
+
+            let index = state.shortlist.partition_point(|(n, _)| comparator.compare(n, &node).is_lt());
+            state.shortlist.insert(index, (node, QueryState::Pending));
+        }
+    }
+
+    fn set_state(&self, node: &Node, query_state: QueryState) {
+        let mut state = self.state.lock().unwrap();
+
+        if let Some(entry) = state.shortlist.iter_mut().find(|(n, _)| n == node) {
+            if entry.1 == QueryState::InFlight {
+                entry.1 = query_state;
+                state.in_flight -= 1;
+            }
+        }
+    }
+
+    //SENDS QUERIES TO THE CLOSEST PENDING NODES UNTIL ALPHA ARE IN FLIGHT, FAILED NODES DONT COUNT TOWARDS K
+    fn advance(&self) {
+        loop {
+            let (queries, closest) = {
+                let mut state = self.state.lock().unwrap();
+
+                if state.finished {
+                    return;
+                }
+
+                let mut queries = Vec::new();
+                let mut in_flight = state.in_flight;
+                let mut converged = true;
+
+                for (node, query_state) in state.shortlist.iter_mut()
+                        .filter(|(_, query_state)| *query_state != QueryState::Failed)
+                        .take(self.k) {
+                    match query_state {
+                        QueryState::Pending => {
+                            converged = false;
+
+                            if in_flight < self.alpha {
+                                *query_state = QueryState::InFlight;
+                                in_flight += 1;
+                                queries.push(*node);
+                            }
+                        }
+                        QueryState::InFlight => converged = false,
+                        _ => {}
+                    }
+                }
+
+                state.in_flight = in_flight;
+
+                if converged {
+                    state.finished = true;
+                    let closest = state.shortlist.iter()
+                        .filter(|(_, query_state)| *query_state == QueryState::Responded)
+                        .take(self.k)
+                        .map(|(node, _)| *node)
+                        .collect();
+                    (queries, Some(closest))
+
+                } else {
+                    (queries, None)
+                }
+            };
+
+            if let Some(closest) = closest {
+                self.handler.on_complete(closest);
+                return;
+            }
+
+            let mut failed = false;
+
+            for node in queries {
+                let mut request = self.handler.create_request(&node);
+                request.set_destination(node.address);
+
+                if let Err(e) = self.kademlia.get_server().lock().unwrap().send_with_node_callback(request.as_mut(), node, Box::new(self.clone())) {
+                    println!("{}", e);
+                    self.set_state(&node, QueryState::Failed);
+                    failed = true;
+                }
+            }
+
+            //A FAILED SEND FREES A SLOT WITHOUT A CALLBACK, SO THE SHORTLIST HAS TO BE WALKED AGAIN
+            if !failed {
+                return;
+            }
+        }
+    }
+}
+
+impl ResponseCallback for IterativeLookup {
+
+    fn on_response(&self, _event: ResponseEvent) {
+        if self.is_finished() {
+            return;
+        }
+
+        let nodes = self.handler.on_response(&_event);
+        self.set_state(&_event.get_node(), QueryState::Responded);
+
+        {
+            let mut state = self.state.lock().unwrap();
+            self.insert(&mut state, nodes);
+        }
+
+        self.advance();
+    }
+
+    fn on_error_response(&self, _event: ErrorResponseEvent) {
+        if _event.has_node() {
+            self.set_state(&_event.get_node(), QueryState::Failed);
+        }
+
+        self.advance();
+    }
+
+    fn on_stalled(&self, _event: StalledEvent) {
+        if _event.has_node() {
+            self.set_state(&_event.get_node(), QueryState::Failed);
+            self.routing_table.lock().unwrap().mark_stale(&_event.get_node());
+        }
+
+        self.advance();
+    }
+}
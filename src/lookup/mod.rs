@@ -0,0 +1,7 @@
+pub mod inter;
+pub mod iterative_lookup;
+pub mod find_node_lookup_handler;
+pub mod get_peers_lookup_handler;
+pub mod get_item_lookup_handler;
+pub mod put_item_lookup_handler;
+pub mod sample_infohashes_lookup_handler;
\ No newline at end of file
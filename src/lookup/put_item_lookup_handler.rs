@@ -0,0 +1,77 @@
+use std::sync::{Arc, Mutex};
+use crate::kad::kademlia_base::KademliaBase;
+use crate::lookup::find_node_lookup_handler::accept_nodes;
+use crate::lookup::inter::lookup_handler::LookupHandler;
+use crate::messages::get_request::GetRequest;
+use crate::messages::get_response::GetResponse;
+use crate::messages::inter::message_base::MessageBase;
+use crate::messages::inter::method_message_base::MethodMessageBase;
+use crate::messages::put_request::PutRequest;
+use crate::routing::inter::routing_table::RoutingTable;
+use crate::rpc::events::inter::message_event::MessageEvent;
+use crate::rpc::events::response_event::ResponseEvent;
+use crate::rpc::ping_response_listener::PingResponseListener;
+use crate::utils::node::Node;
+use crate::utils::uid::UID;
+
+//GET LOOKUP TOWARDS THE TARGET, ONCE IT CONVERGES THE PUT IS SENT TO THE CLOSEST NODES WITH THEIR TOKENS
+pub struct PutItemLookupHandler {
+    kademlia: Box<dyn KademliaBase>,
+    routing_table: Arc<Mutex<dyn RoutingTable>>,
+    target: UID,
+    request: PutRequest,
+    tokens: Mutex<Vec<(Node, Vec<u8>)>>
+}
+
+impl PutItemLookupHandler {
+
+    pub fn new(kademlia: &dyn KademliaBase, routing_table: Arc<Mutex<dyn RoutingTable>>, target: UID, request: PutRequest) -> Self {
+        Self {
+            kademlia: kademlia.clone_dyn(),
+            routing_table,
+            target,
+            request,
+            tokens: Mutex::new(Vec::new())
+        }
+    }
+}
+
+impl LookupHandler for PutItemLookupHandler {
+
+    fn create_request(&self, _node: &Node) -> Box<dyn MethodMessageBase> {
+        let mut request = GetRequest::default();
+        request.set_target(self.target);
+        Box::new(request)
+    }
+
+    fn on_response(&self, event: &ResponseEvent) -> Vec<Node> {
+        let response = event.get_message().as_any().downcast_ref::<GetResponse>().unwrap();
+
+        if let Some(token) = response.get_token() {
+            self.tokens.lock().unwrap().push((event.get_node(), token.clone()));
+        }
+
+        accept_nodes(&self.routing_table, event, response.get_all_nodes())
+    }
+
+    fn on_complete(&self, closest: Vec<Node>) {
+        let tokens = self.tokens.lock().unwrap();
+
+        for node in closest {
+            let token = match tokens.iter().find(|(n, _)| *n == node) {
+                Some((_, token)) => token,
+                None => continue
+            };
+
+            let mut request = self.request.clone();
+            request.set_destination(node.address);
+            request.set_token(token);
+
+            let listener = PingResponseListener::new(self.kademlia.get_routing_table_for(&node.address).clone());
+
+            if let Err(e) = self.kademlia.get_server().lock().unwrap().send_with_node_callback(&mut request, node, Box::new(listener)) {
+                println!("{}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::kad::kademlia_base::KademliaBase;
+use crate::lookup::find_node_lookup_handler::accept_nodes;
+use crate::lookup::inter::lookup_handler::LookupHandler;
+use crate::lookup::iterative_lookup::IterativeLookup;
+use crate::messages::find_node_request::FindNodeRequest;
+use crate::messages::find_node_response::FindNodeResponse;
+use crate::messages::inter::method_message_base::MethodMessageBase;
+use crate::messages::sample_infohashes_request::SampleInfohashesRequest;
+use crate::messages::sample_infohashes_response::SampleInfohashesResponse;
+use crate::routing::inter::routing_table::RoutingTable;
+use crate::rpc::events::inter::message_event::MessageEvent;
+use crate::rpc::events::response_event::ResponseEvent;
+use crate::utils::node::Node;
+use crate::utils::uid::{ID_LENGTH, UID};
+
+pub const MAX_SAMPLED_NODES: usize = 4096;
+//NODES THAT ASK FOR NO INTERVAL ARE STILL ONLY SAMPLED ONCE A MINUTE
+pub const MIN_SAMPLE_INTERVAL: u128 = 60000;
+
+pub type SampleCallback = Arc<dyn Fn(Node, &SampleInfohashesResponse) + Send + Sync>;
+
+//NODES THAT HAVE BEEN SAMPLED, WITH THE TIME THEIR BEP 51 interval RUNS OUT
+#[derive(Default, Debug)]
+pub struct SampledNodes {
+    nodes: HashMap<UID, u128>
+}
+
+impl SampledNodes {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn can_sample(&self, uid: &UID, now: u128) -> bool {
+        !matches!(self.nodes.get(uid), Some(until) if *until > now)
+    }
+
+    //ONCE FULL THE EXPIRED ENTRIES ARE DROPPED, THEN THE ONE THAT RUNS OUT SOONEST
+    pub fn insert(&mut self, uid: UID, until: u128, now: u128) {
+        if !self.nodes.contains_key(&uid) && self.nodes.len() >= MAX_SAMPLED_NODES {
+            self.nodes.retain(|_, until| *until > now);
+
+            if self.nodes.len() >= MAX_SAMPLED_NODES {
+                if let Some(soonest) = self.nodes.iter().min_by_key(|(_, until)| **until).map(|(uid, _)| *uid) {
+                    self.nodes.remove(&soonest);
+                }
+            }
+        }
+
+        self.nodes.insert(uid, until);
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+//WALKS THE KEYSPACE ONE DISTANCE AT A TIME, EVERY DISTANCE IS ITS OWN LOOKUP SO AT MOST ALPHA QUERIES ARE IN FLIGHT
+#[derive(Clone)]
+pub struct SampleInfohashesWalk {
+    kademlia: Box<dyn KademliaBase>,
+    routing_table: Arc<Mutex<dyn RoutingTable>>,
+    sampled: Arc<Mutex<SampledNodes>>,
+    callback: SampleCallback,
+    distance: Arc<Mutex<usize>>
+}
+
+impl SampleInfohashesWalk {
+
+    pub fn new(kademlia: &dyn KademliaBase, routing_table: Arc<Mutex<dyn RoutingTable>>, sampled: Arc<Mutex<SampledNodes>>, callback: SampleCallback) -> Self {
+        Self {
+            kademlia: kademlia.clone_dyn(),
+            routing_table,
+            sampled,
+            callback,
+            distance: Arc::new(Mutex::new(1))
+        }
+    }
+
+    pub fn start(&self) -> io::Result<()> {
+        if !self.next() {
+            return Err(io::Error::new(io::ErrorKind::NotConnected, "No nodes to query"));
+        }
+
+        Ok(())
+    }
+
+    //STARTS THE LOOKUP OF THE NEXT DISTANCE THAT HAS NODES TO QUERY, FALSE ONCE EVERY DISTANCE WAS WALKED
+    fn next(&self) -> bool {
+        while let Some(target) = self.next_target() {
+            let handler = SampleInfohashesLookupHandler {
+                walk: self.clone(),
+                target
+            };
+
+            if IterativeLookup::new(self.kademlia.as_ref(), self.routing_table.clone(), target, Arc::new(handler)).start().is_ok() {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    //DISTANCES 1 THROUGH 160, THE LAST ONE IS THE FAR HALF OF THE KEYSPACE
+    fn next_target(&self) -> Option<UID> {
+        let distance = {
+            let mut distance = self.distance.lock().unwrap();
+
+            if *distance > ID_LENGTH*8 {
+                return None;
+            }
+
+            *distance += 1;
+            *distance-1
+        };
+
+        Some(self.routing_table.lock().unwrap().get_derived_uid().generate_node_id_by_distance(distance))
+    }
+}
+
+pub struct SampleInfohashesLookupHandler {
+    walk: SampleInfohashesWalk,
+    target: UID
+}
+
+impl LookupHandler for SampleInfohashesLookupHandler {
+
+    //NODES STILL WITHIN THEIR interval ARE ONLY ASKED FOR CLOSER NODES
+    fn create_request(&self, node: &Node) -> Box<dyn MethodMessageBase> {
+        if self.walk.sampled.lock().unwrap().can_sample(&node.uid, now()) {
+            let mut request = SampleInfohashesRequest::default();
+            request.set_target(self.target);
+            return Box::new(request);
+        }
+
+        let mut request = FindNodeRequest::default();
+        request.set_target(self.target);
+        Box::new(request)
+    }
+
+    fn on_response(&self, event: &ResponseEvent) -> Vec<Node> {
+        let nodes = if let Some(response) = event.get_message().as_any().downcast_ref::<SampleInfohashesResponse>() {
+            let now = now();
+            let interval = (response.get_interval() as u128*1000).max(MIN_SAMPLE_INTERVAL);
+            self.walk.sampled.lock().unwrap().insert(event.get_node().uid, now+interval, now);
+
+            (self.walk.callback)(event.get_node(), response);
+            response.get_all_nodes()
+
+        } else if let Some(response) = event.get_message().as_any().downcast_ref::<FindNodeResponse>() {
+            response.get_all_nodes()
+
+        } else {
+            Vec::new()
+        };
+
+        accept_nodes(&self.walk.routing_table, event, nodes)
+    }
+
+    fn on_complete(&self, _closest: Vec<Node>) {
+        self.walk.next();
+    }
+}
+
+fn now() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_millis()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::kad::kademlia_builder::KademliaBuilder;
+    use super::*;
+
+    fn bid(i: usize) -> [u8; ID_LENGTH] {
+        let mut bid = [0u8; ID_LENGTH];
+        bid[..8].copy_from_slice(&(i as u64).to_be_bytes());
+        bid
+    }
+
+    #[test]
+    fn sampled_nodes() {
+        let mut sampled = SampledNodes::new();
+        let uid = UID::from([1u8; ID_LENGTH]);
+        assert!(sampled.can_sample(&uid, 0));
+
+        sampled.insert(uid, 1000, 0);
+        assert!(!sampled.can_sample(&uid, 999));
+        assert!(sampled.can_sample(&uid, 1000));
+
+        for i in 0..MAX_SAMPLED_NODES*2 {
+            sampled.insert(UID::from(bid(i)), 2000+i as u128, 0);
+        }
+
+        //THE ENTRIES THAT RUN OUT SOONEST MAKE ROOM
+        assert_eq!(sampled.len(), MAX_SAMPLED_NODES);
+        assert!(sampled.can_sample(&uid, 0));
+        assert!(!sampled.can_sample(&UID::from(bid(MAX_SAMPLED_NODES*2-1)), 0));
+    }
+
+    #[test]
+    fn walk_targets() {
+        let kademlia = KademliaBuilder::new().secure_only(false).build().unwrap();
+        let routing_table = kademlia.get_routing_table().clone();
+        let uid = routing_table.lock().unwrap().get_derived_uid();
+        let walk = SampleInfohashesWalk::new(&kademlia, routing_table, Arc::new(Mutex::new(SampledNodes::new())), Arc::new(|_, _| {}));
+
+        let mut distances = Vec::new();
+        while let Some(target) = walk.next_target() {
+            distances.push(uid.distance(&target));
+        }
+
+        assert!(distances == (1..=ID_LENGTH*8).collect::<Vec<usize>>());
+    }
+}
@@ -0,0 +1,236 @@
+use std::any::Any;
+use std::net::SocketAddr;
+use rlibbencode::variables::bencode_bytes::BencodeBytes;
+use rlibbencode::variables::bencode_number::BencodeNumber;
+use rlibbencode::variables::bencode_object::{BencodeObject, GetObject, ObjectOptions, PutObject};
+use rlibbencode::variables::inter::bencode_variable::BencodeVariable;
+use crate::messages::inter::message_base::{MessageBase, TID_KEY};
+use crate::messages::inter::message_exception::MessageException;
+use crate::messages::inter::message_type::{MessageType, TYPE_KEY};
+use crate::utils::uid::{ID_LENGTH, UID};
+use super::inter::method_message_base::MethodMessageBase;
+
+#[derive(Clone, Default)]
+pub struct AnnouncePeerRequest {
+    uid: Option<UID>,
+    tid: Vec<u8>,
+    public: Option<SocketAddr>,
+    destination: Option<SocketAddr>,
+    origin: Option<SocketAddr>,
+    info_hash: Option<UID>,
+    port: u16,
+    implied_port: bool,
+    token: Option<Vec<u8>>,
+    seed: bool
+}
+
+impl AnnouncePeerRequest {
+
+    pub fn new(tid: &[u8]) -> Self {
+        Self {
+            tid: tid.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    pub fn set_info_hash(&mut self, info_hash: UID) {
+        self.info_hash = Some(info_hash);
+    }
+
+    pub fn get_info_hash(&self) -> Option<UID> {
+        self.info_hash
+    }
+
+    pub fn set_port(&mut self, port: u16) {
+        self.port = port;
+    }
+
+    pub fn get_port(&self) -> u16 {
+        self.port
+    }
+
+    pub fn set_implied_port(&mut self, implied_port: bool) {
+        self.implied_port = implied_port;
+    }
+
+    pub fn is_implied_port(&self) -> bool {
+        self.implied_port
+    }
+
+    pub fn set_token(&mut self, token: &[u8]) {
+        self.token = Some(token.to_vec());
+    }
+
+    pub fn get_token(&self) -> Option<&Vec<u8>> {
+        self.token.as_ref()
+    }
+
+    pub fn set_seed(&mut self, seed: bool) {
+        self.seed = seed;
+    }
+
+    pub fn is_seed(&self) -> bool {
+        self.seed
+    }
+}
+
+impl MessageBase for AnnouncePeerRequest {
+
+    fn set_uid(&mut self, uid: UID) {
+        self.uid = Some(uid);
+    }
+
+    fn get_uid(&self) -> Option<UID> {
+        self.uid
+    }
+
+    fn set_transaction_id(&mut self, tid: &[u8]) {
+        self.tid = tid.to_vec();
+    }
+
+    fn get_transaction_id(&self) -> &[u8] {
+        &self.tid
+    }
+
+    fn set_public(&mut self, public: SocketAddr) {
+        self.public = Some(public);
+    }
+
+    fn get_public(&self) -> Option<SocketAddr> {
+        self.public
+    }
+
+    fn set_destination(&mut self, destination: SocketAddr) {
+        self.destination = Some(destination);
+    }
+
+    fn get_destination(&self) -> Option<SocketAddr> {
+        self.destination
+    }
+
+    fn set_origin(&mut self, origin: SocketAddr) {
+        self.origin = Some(origin);
+    }
+
+    fn get_origin(&self) -> Option<SocketAddr> {
+        self.origin
+    }
+
+    fn get_type(&self) -> MessageType {
+        MessageType::ReqMsg
+    }
+
+    fn encode(&self) -> BencodeObject {
+        let mut ben = BencodeObject::new();
+
+        ben.put(TID_KEY, self.tid.clone());
+        ben.put(TYPE_KEY, self.get_type().rpc_type_name());
+
+        ben.put(self.get_type().rpc_type_name(), self.get_method());
+        ben.put(self.get_type().inner_key(), BencodeObject::new());
+
+        let inner = ben.get_mut::<BencodeObject>(self.get_type().inner_key()).unwrap();
+        inner.put("id", self.uid.unwrap().bytes());
+
+        if let Some(info_hash) = self.info_hash {
+            inner.put("info_hash", info_hash.bytes());
+        }
+
+        inner.put("port", self.port);
+
+        if self.implied_port {
+            inner.put("implied_port", 1);
+        }
+
+        if let Some(token) = &self.token {
+            inner.put("token", token.clone());
+        }
+
+        if self.seed {
+            inner.put("seed", 1);
+        }
+
+        ben
+    }
+
+    fn decode(&mut self, ben: &BencodeObject) -> Result<(), MessageException> {
+        if !ben.contains_key(self.get_type().inner_key()) {
+            return Err(MessageException::new("Protocol Error, such as a malformed packet.", 203));
+        }
+
+        let inner = ben.get::<BencodeObject>(self.get_type().inner_key()).unwrap();
+
+        match inner.get::<BencodeBytes>("id") {
+            Some(id) if id.as_bytes().len() == ID_LENGTH => {
+                let mut bid = [0u8; ID_LENGTH];
+                bid.copy_from_slice(&id.as_bytes()[..ID_LENGTH]);
+                self.uid = Some(UID::from(bid));
+            }
+            _ => return Err(MessageException::new("Protocol Error, such as a malformed packet.", 203))
+        }
+
+        match inner.get::<BencodeBytes>("info_hash") {
+            Some(info_hash) if info_hash.as_bytes().len() == ID_LENGTH => {
+                let mut bid = [0u8; ID_LENGTH];
+                bid.copy_from_slice(&info_hash.as_bytes()[..ID_LENGTH]);
+                self.info_hash = Some(UID::from(bid));
+            }
+            _ => return Err(MessageException::new("Protocol Error, such as a malformed packet.", 203))
+        }
+
+        match inner.get::<BencodeBytes>("token") {
+            Some(token) => self.token = Some(token.as_bytes().to_vec()),
+            _ => return Err(MessageException::new("Protocol Error, such as a malformed packet.", 203))
+        }
+
+        if let Some(implied_port) = inner.get::<BencodeNumber>("implied_port") {
+            self.implied_port = implied_port.parse::<i64>()
+                .map_err(|_| MessageException::new("Protocol Error, such as a malformed packet.", 203))? != 0;
+        }
+
+        if let Some(seed) = inner.get::<BencodeNumber>("seed") {
+            self.seed = seed.parse::<i64>()
+                .map_err(|_| MessageException::new("Protocol Error, such as a malformed packet.", 203))? != 0;
+        }
+
+        match inner.get::<BencodeNumber>("port") {
+            Some(port) => {
+                let port = port.parse::<i64>()
+                    .map_err(|_| MessageException::new("Protocol Error, such as a malformed packet.", 203))?;
+                self.port = u16::try_from(port)
+                    .map_err(|_| MessageException::new("Protocol Error, such as a malformed packet.", 203))?;
+            }
+            _ if self.implied_port => {}
+            _ => return Err(MessageException::new("Protocol Error, such as a malformed packet.", 203))
+        }
+
+        Ok(())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl MethodMessageBase for AnnouncePeerRequest {
+
+    fn get_method(&self) -> &str {
+        "announce_peer"
+    }
+
+    fn upcast(&self) -> &dyn MessageBase {
+        self
+    }
+
+    fn upcast_mut(&mut self) -> &mut dyn MessageBase {
+        self
+    }
+
+    fn dyn_clone(&self) -> Box<dyn MethodMessageBase> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,140 @@
+use std::any::Any;
+use std::net::SocketAddr;
+use rlibbencode::variables::bencode_bytes::BencodeBytes;
+use rlibbencode::variables::bencode_object::{BencodeObject, GetObject, ObjectOptions, PutObject};
+use crate::messages::inter::message_base::{MessageBase, TID_KEY};
+use crate::messages::inter::message_exception::MessageException;
+use crate::messages::inter::message_type::{MessageType, TYPE_KEY};
+use crate::utils::net::address_utils::{pack_address, unpack_address};
+use crate::utils::uid::{ID_LENGTH, UID};
+use super::inter::method_message_base::MethodMessageBase;
+
+#[derive(Clone, Default)]
+pub struct AnnouncePeerResponse {
+    uid: Option<UID>,
+    tid: Vec<u8>,
+    public: Option<SocketAddr>,
+    destination: Option<SocketAddr>,
+    origin: Option<SocketAddr>
+}
+
+impl AnnouncePeerResponse {
+
+    pub fn new(tid: &[u8]) -> Self {
+        Self {
+            tid: tid.to_vec(),
+            ..Default::default()
+        }
+    }
+}
+
+impl MessageBase for AnnouncePeerResponse {
+
+    fn set_uid(&mut self, uid: UID) {
+        self.uid = Some(uid);
+    }
+
+    fn get_uid(&self) -> Option<UID> {
+        self.uid
+    }
+
+    fn set_transaction_id(&mut self, tid: &[u8]) {
+        self.tid = tid.to_vec();
+    }
+
+    fn get_transaction_id(&self) -> &[u8] {
+        &self.tid
+    }
+
+    fn set_public(&mut self, public: SocketAddr) {
+        self.public = Some(public);
+    }
+
+    fn get_public(&self) -> Option<SocketAddr> {
+        self.public
+    }
+
+    fn set_destination(&mut self, destination: SocketAddr) {
+        self.destination = Some(destination);
+    }
+
+    fn get_destination(&self) -> Option<SocketAddr> {
+        self.destination
+    }
+
+    fn set_origin(&mut self, origin: SocketAddr) {
+        self.origin = Some(origin);
+    }
+
+    fn get_origin(&self) -> Option<SocketAddr> {
+        self.origin
+    }
+
+    fn get_type(&self) -> MessageType {
+        MessageType::RspMsg
+    }
+
+    fn encode(&self) -> BencodeObject {
+        let mut ben = BencodeObject::new();
+
+        ben.put(TID_KEY, self.tid.clone());
+        ben.put(TYPE_KEY, self.get_type().rpc_type_name());
+
+        ben.put(self.get_type().inner_key(), BencodeObject::new());
+        ben.get_mut::<BencodeObject>(self.get_type().inner_key()).unwrap().put("id", self.uid.unwrap().bytes());
+
+        if let Some(public) = self.public {
+            ben.put("ip", pack_address(&public));
+        }
+
+        ben
+    }
+
+    fn decode(&mut self, ben: &BencodeObject) -> Result<(), MessageException> {
+        if !ben.contains_key(self.get_type().inner_key()) {
+            return Err(MessageException::new("Protocol Error, such as a malformed packet.", 203));
+        }
+
+        match ben.get::<BencodeObject>(self.get_type().inner_key()).unwrap().get::<BencodeBytes>("id") {
+            Some(id) if id.as_bytes().len() == ID_LENGTH => {
+                let mut bid = [0u8; ID_LENGTH];
+                bid.copy_from_slice(&id.as_bytes()[..ID_LENGTH]);
+                self.uid = Some(UID::from(bid));
+            }
+            _ => return Err(MessageException::new("Protocol Error, such as a malformed packet.", 203))
+        }
+
+        if let Some(addr) = ben.get::<BencodeBytes>("ip") {
+            self.public = unpack_address(addr.as_bytes()).ok();
+        }
+
+        Ok(())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl MethodMessageBase for AnnouncePeerResponse {
+
+    fn get_method(&self) -> &str {
+        "announce_peer"
+    }
+
+    fn upcast(&self) -> &dyn MessageBase {
+        self
+    }
+
+    fn upcast_mut(&mut self) -> &mut dyn MessageBase {
+        self
+    }
+
+    fn dyn_clone(&self) -> Box<dyn MethodMessageBase> {
+        Box::new(self.clone())
+    }
+}
@@ -5,17 +5,16 @@ use rlibbencode::variables::bencode_bytes::BencodeBytes;
 use rlibbencode::variables::bencode_number::BencodeNumber;
 use rlibbencode::variables::bencode_object::{BencodeObject, GetObject, ObjectOptions, PutObject};
 use rlibbencode::variables::inter::bencode_variable::BencodeVariable;
-use crate::kad::server::TID_LENGTH;
 use crate::messages::inter::message_base::{MessageBase, TID_KEY};
 use crate::messages::inter::message_exception::MessageException;
 use crate::messages::inter::message_type::{MessageType, TYPE_KEY};
 use crate::utils::net::address_utils::pack_address;
 use crate::utils::uid::UID;
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct ErrorResponse {
     uid: Option<UID>,
-    tid: [u8; TID_LENGTH],
+    tid: Vec<u8>,
     public: Option<SocketAddr>,
     destination: Option<SocketAddr>,
     origin: Option<SocketAddr>,
@@ -25,9 +24,9 @@ pub struct ErrorResponse {
 
 impl ErrorResponse {
 
-    pub fn new(tid: [u8; TID_LENGTH]) -> Self {
+    pub fn new(tid: &[u8]) -> Self {
         Self {
-            tid,
+            tid: tid.to_vec(),
             ..Default::default()
         }
     }
@@ -49,21 +48,6 @@ impl ErrorResponse {
     }
 }
 
-impl Default for ErrorResponse {
-
-    fn default() -> Self {
-        Self {
-            uid: None,
-            tid: [0u8; TID_LENGTH],
-            public: None,
-            destination: None,
-            origin: None,
-            code: 0,
-            description: None
-        }
-    }
-}
-
 impl MessageBase for ErrorResponse {
 
     fn set_uid(&mut self, uid: UID) {
@@ -74,11 +58,11 @@ impl MessageBase for ErrorResponse {
         self.uid
     }
 
-    fn set_transaction_id(&mut self, tid: [u8; TID_LENGTH]) {
-        self.tid = tid;
+    fn set_transaction_id(&mut self, tid: &[u8]) {
+        self.tid = tid.to_vec();
     }
 
-    fn get_transaction_id(&self) -> &[u8; TID_LENGTH] {
+    fn get_transaction_id(&self) -> &[u8] {
         &self.tid
     }
 
@@ -114,7 +98,6 @@ impl MessageBase for ErrorResponse {
         let mut ben = BencodeObject::new();
         
         ben.put(TID_KEY, self.tid.clone());
-        ben.put("v", "1.0");
         ben.put(TYPE_KEY, self.get_type().rpc_type_name());
 
         let mut arr = BencodeArray::new();
@@ -1,29 +1,31 @@
 use std::any::Any;
 use std::net::SocketAddr;
+use rlibbencode::variables::bencode_array::{AddArray, BencodeArray};
 use rlibbencode::variables::bencode_bytes::BencodeBytes;
 use rlibbencode::variables::bencode_object::{BencodeObject, GetObject, ObjectOptions, PutObject};
-use crate::kad::server::TID_LENGTH;
 use crate::messages::inter::message_base::{MessageBase, TID_KEY};
 use crate::messages::inter::message_exception::MessageException;
 use crate::messages::inter::message_type::{MessageType, TYPE_KEY};
+use crate::utils::net::address_types::AddressTypes;
 use crate::utils::uid::{ID_LENGTH, UID};
 use super::inter::method_message_base::MethodMessageBase;
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct FindNodeRequest {
     uid: Option<UID>,
-    tid: [u8; TID_LENGTH],
+    tid: Vec<u8>,
     public: Option<SocketAddr>,
     destination: Option<SocketAddr>,
     origin: Option<SocketAddr>,
-    target: Option<UID>
+    target: Option<UID>,
+    want: Vec<AddressTypes>
 }
 
 impl FindNodeRequest {
 
-    pub fn new(tid: [u8; TID_LENGTH]) -> Self {
+    pub fn new(tid: &[u8]) -> Self {
         Self {
-            tid,
+            tid: tid.to_vec(),
             ..Default::default()
         }
     }
@@ -35,19 +37,17 @@ impl FindNodeRequest {
     pub fn get_target(&self) -> Option<UID> {
         self.target
     }
-}
 
-impl Default for FindNodeRequest {
+    pub fn set_want(&mut self, want: Vec<AddressTypes>) {
+        self.want = want;
+    }
 
-    fn default() -> Self {
-        Self {
-            uid: None,
-            tid: [0u8; TID_LENGTH],
-            public: None,
-            destination: None,
-            origin: None,
-            target: None
-        }
+    pub fn get_want(&self) -> &Vec<AddressTypes> {
+        &self.want
+    }
+
+    pub fn has_want(&self) -> bool {
+        !self.want.is_empty()
     }
 }
 
@@ -62,11 +62,11 @@ impl MessageBase for FindNodeRequest {
         self.uid
     }
 
-    fn set_transaction_id(&mut self, tid: [u8; TID_LENGTH]) {
-        self.tid = tid;
+    fn set_transaction_id(&mut self, tid: &[u8]) {
+        self.tid = tid.to_vec();
     }
 
-    fn get_transaction_id(&self) -> &[u8; TID_LENGTH] {
+    fn get_transaction_id(&self) -> &[u8] {
         &self.tid
     }
 
@@ -102,7 +102,6 @@ impl MessageBase for FindNodeRequest {
         let mut ben = BencodeObject::new();
 
         ben.put(TID_KEY, self.tid.clone());
-        ben.put("v", "1.0");
         ben.put(TYPE_KEY, self.get_type().rpc_type_name());
 
         ben.put(self.get_type().rpc_type_name(), self.get_method());
@@ -113,6 +112,14 @@ impl MessageBase for FindNodeRequest {
             ben.get_mut::<BencodeObject>(self.get_type().inner_key()).unwrap().put("target", target.bytes().clone());
         }
 
+        if !self.want.is_empty() {
+            let mut want = BencodeArray::new();
+            for address_type in &self.want {
+                want.push(address_type.want());
+            }
+            ben.get_mut::<BencodeObject>(self.get_type().inner_key()).unwrap().put("want", want);
+        }
+
         ben
     }
 
@@ -139,6 +146,16 @@ impl MessageBase for FindNodeRequest {
             _ => return Err(MessageException::new("Protocol Error, such as a malformed packet.", 203))
         }
 
+        if let Some(want) = ben.get::<BencodeObject>(self.get_type().inner_key()).unwrap().get::<BencodeArray>("want") {
+            for i in 0..want.len() {
+                if let Some(address_type) = want.get::<BencodeBytes>(i).and_then(|w| AddressTypes::from_want(w.as_str())) {
+                    if !self.want.contains(&address_type) {
+                        self.want.push(address_type);
+                    }
+                }
+            }
+        }
+
         Ok(())
     }
 
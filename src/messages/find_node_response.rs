@@ -2,7 +2,6 @@ use std::any::Any;
 use std::net::SocketAddr;
 use rlibbencode::variables::bencode_bytes::BencodeBytes;
 use rlibbencode::variables::bencode_object::{BencodeObject, GetObject, ObjectOptions, PutObject};
-use crate::kad::server::TID_LENGTH;
 use crate::messages::inter::message_base::{MessageBase, TID_KEY};
 use crate::messages::inter::message_exception::MessageException;
 use crate::messages::inter::message_type::{MessageType, TYPE_KEY};
@@ -15,10 +14,10 @@ use super::inter::method_message_base::MethodMessageBase;
 
 pub const NODE_CAP: usize = 20;
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct FindNodeResponse {
     uid: Option<UID>,
-    tid: [u8; TID_LENGTH],
+    tid: Vec<u8>,
     public: Option<SocketAddr>,
     destination: Option<SocketAddr>,
     origin: Option<SocketAddr>,
@@ -27,9 +26,9 @@ pub struct FindNodeResponse {
 
 impl FindNodeResponse {
 
-    pub fn new(tid: [u8; TID_LENGTH]) -> Self {
+    pub fn new(tid: &[u8]) -> Self {
         Self {
-            tid,
+            tid: tid.to_vec(),
             ..Default::default()
         }
     }
@@ -90,20 +89,6 @@ impl FindNodeResponse {
     }
 }
 
-impl Default for FindNodeResponse {
-
-    fn default() -> Self {
-        Self {
-            uid: None,
-            tid: [0u8; TID_LENGTH],
-            public: None,
-            destination: None,
-            origin: None,
-            nodes: Vec::new()
-        }
-    }
-}
-
 //I WONDER IF WE CAN MACRO THIS SHIT FOR EVERY CLASS...?
 impl MessageBase for FindNodeResponse {
 
@@ -115,11 +100,11 @@ impl MessageBase for FindNodeResponse {
         self.uid
     }
 
-    fn set_transaction_id(&mut self, tid: [u8; TID_LENGTH]) {
-        self.tid = tid;
+    fn set_transaction_id(&mut self, tid: &[u8]) {
+        self.tid = tid.to_vec();
     }
 
-    fn get_transaction_id(&self) -> &[u8; TID_LENGTH] {
+    fn get_transaction_id(&self) -> &[u8] {
         &self.tid
     }
 
@@ -155,7 +140,6 @@ impl MessageBase for FindNodeResponse {
         let mut ben = BencodeObject::new();
 
         ben.put(TID_KEY, self.tid.clone());
-        ben.put("v", "1.0");
         ben.put(TYPE_KEY, self.get_type().rpc_type_name());
 
         ben.put(self.get_type().rpc_type_name(), self.get_method());
@@ -0,0 +1,225 @@
+use std::any::Any;
+use std::net::SocketAddr;
+use rlibbencode::variables::bencode_array::{AddArray, BencodeArray};
+use rlibbencode::variables::bencode_bytes::BencodeBytes;
+use rlibbencode::variables::bencode_number::BencodeNumber;
+use rlibbencode::variables::bencode_object::{BencodeObject, GetObject, ObjectOptions, PutObject};
+use rlibbencode::variables::inter::bencode_variable::BencodeVariable;
+use crate::messages::inter::message_base::{MessageBase, TID_KEY};
+use crate::messages::inter::message_exception::MessageException;
+use crate::messages::inter::message_type::{MessageType, TYPE_KEY};
+use crate::utils::net::address_types::AddressTypes;
+use crate::utils::uid::{ID_LENGTH, UID};
+use super::inter::method_message_base::MethodMessageBase;
+
+#[derive(Clone, Default)]
+pub struct GetPeersRequest {
+    uid: Option<UID>,
+    tid: Vec<u8>,
+    public: Option<SocketAddr>,
+    destination: Option<SocketAddr>,
+    origin: Option<SocketAddr>,
+    info_hash: Option<UID>,
+    want: Vec<AddressTypes>,
+    scrape: bool,
+    no_seed: bool
+}
+
+impl GetPeersRequest {
+
+    pub fn new(tid: &[u8]) -> Self {
+        Self {
+            tid: tid.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    pub fn set_info_hash(&mut self, info_hash: UID) {
+        self.info_hash = Some(info_hash);
+    }
+
+    pub fn get_info_hash(&self) -> Option<UID> {
+        self.info_hash
+    }
+
+    pub fn set_want(&mut self, want: Vec<AddressTypes>) {
+        self.want = want;
+    }
+
+    pub fn get_want(&self) -> &Vec<AddressTypes> {
+        &self.want
+    }
+
+    pub fn has_want(&self) -> bool {
+        !self.want.is_empty()
+    }
+
+    pub fn set_scrape(&mut self, scrape: bool) {
+        self.scrape = scrape;
+    }
+
+    pub fn is_scrape(&self) -> bool {
+        self.scrape
+    }
+
+    pub fn set_no_seed(&mut self, no_seed: bool) {
+        self.no_seed = no_seed;
+    }
+
+    pub fn is_no_seed(&self) -> bool {
+        self.no_seed
+    }
+}
+
+impl MessageBase for GetPeersRequest {
+
+    fn set_uid(&mut self, uid: UID) {
+        self.uid = Some(uid);
+    }
+
+    fn get_uid(&self) -> Option<UID> {
+        self.uid
+    }
+
+    fn set_transaction_id(&mut self, tid: &[u8]) {
+        self.tid = tid.to_vec();
+    }
+
+    fn get_transaction_id(&self) -> &[u8] {
+        &self.tid
+    }
+
+    fn set_public(&mut self, public: SocketAddr) {
+        self.public = Some(public);
+    }
+
+    fn get_public(&self) -> Option<SocketAddr> {
+        self.public
+    }
+
+    fn set_destination(&mut self, destination: SocketAddr) {
+        self.destination = Some(destination);
+    }
+
+    fn get_destination(&self) -> Option<SocketAddr> {
+        self.destination
+    }
+
+    fn set_origin(&mut self, origin: SocketAddr) {
+        self.origin = Some(origin);
+    }
+
+    fn get_origin(&self) -> Option<SocketAddr> {
+        self.origin
+    }
+
+    fn get_type(&self) -> MessageType {
+        MessageType::ReqMsg
+    }
+
+    fn encode(&self) -> BencodeObject {
+        let mut ben = BencodeObject::new();
+
+        ben.put(TID_KEY, self.tid.clone());
+        ben.put(TYPE_KEY, self.get_type().rpc_type_name());
+
+        ben.put(self.get_type().rpc_type_name(), self.get_method());
+        ben.put(self.get_type().inner_key(), BencodeObject::new());
+        ben.get_mut::<BencodeObject>(self.get_type().inner_key()).unwrap().put("id", self.uid.unwrap().bytes());
+
+        if let Some(info_hash) = self.info_hash {
+            ben.get_mut::<BencodeObject>(self.get_type().inner_key()).unwrap().put("info_hash", info_hash.bytes());
+        }
+
+        if !self.want.is_empty() {
+            let mut want = BencodeArray::new();
+            for address_type in &self.want {
+                want.push(address_type.want());
+            }
+            ben.get_mut::<BencodeObject>(self.get_type().inner_key()).unwrap().put("want", want);
+        }
+
+        if self.scrape {
+            ben.get_mut::<BencodeObject>(self.get_type().inner_key()).unwrap().put("scrape", 1);
+        }
+
+        if self.no_seed {
+            ben.get_mut::<BencodeObject>(self.get_type().inner_key()).unwrap().put("noseed", 1);
+        }
+
+        ben
+    }
+
+    fn decode(&mut self, ben: &BencodeObject) -> Result<(), MessageException> {
+        if !ben.contains_key(self.get_type().inner_key()) {
+            return Err(MessageException::new("Protocol Error, such as a malformed packet.", 203));
+        }
+
+        match ben.get::<BencodeObject>(self.get_type().inner_key()).unwrap().get::<BencodeBytes>("id") {
+            Some(id) if id.as_bytes().len() == ID_LENGTH => {
+                let mut bid = [0u8; ID_LENGTH];
+                bid.copy_from_slice(&id.as_bytes()[..ID_LENGTH]);
+                self.uid = Some(UID::from(bid));
+            }
+            _ => return Err(MessageException::new("Protocol Error, such as a malformed packet.", 203))
+        }
+
+        match ben.get::<BencodeObject>(self.get_type().inner_key()).unwrap().get::<BencodeBytes>("info_hash") {
+            Some(info_hash) if info_hash.as_bytes().len() == ID_LENGTH => {
+                let mut bid = [0u8; ID_LENGTH];
+                bid.copy_from_slice(&info_hash.as_bytes()[..ID_LENGTH]);
+                self.info_hash = Some(UID::from(bid));
+            }
+            _ => return Err(MessageException::new("Protocol Error, such as a malformed packet.", 203))
+        }
+
+        if let Some(want) = ben.get::<BencodeObject>(self.get_type().inner_key()).unwrap().get::<BencodeArray>("want") {
+            for i in 0..want.len() {
+                if let Some(address_type) = want.get::<BencodeBytes>(i).and_then(|w| AddressTypes::from_want(w.as_str())) {
+                    if !self.want.contains(&address_type) {
+                        self.want.push(address_type);
+                    }
+                }
+            }
+        }
+
+        if let Some(scrape) = ben.get::<BencodeObject>(self.get_type().inner_key()).unwrap().get::<BencodeNumber>("scrape") {
+            self.scrape = scrape.parse::<i64>()
+                .map_err(|_| MessageException::new("Protocol Error, such as a malformed packet.", 203))? != 0;
+        }
+
+        if let Some(no_seed) = ben.get::<BencodeObject>(self.get_type().inner_key()).unwrap().get::<BencodeNumber>("noseed") {
+            self.no_seed = no_seed.parse::<i64>()
+                .map_err(|_| MessageException::new("Protocol Error, such as a malformed packet.", 203))? != 0;
+        }
+
+        Ok(())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl MethodMessageBase for GetPeersRequest {
+
+    fn get_method(&self) -> &str {
+        "get_peers"
+    }
+
+    fn upcast(&self) -> &dyn MessageBase {
+        self
+    }
+
+    fn upcast_mut(&mut self) -> &mut dyn MessageBase {
+        self
+    }
+
+    fn dyn_clone(&self) -> Box<dyn MethodMessageBase> {
+        Box::new(self.clone())
+    }
+}
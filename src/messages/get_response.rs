@@ -0,0 +1,284 @@
+use std::any::Any;
+use std::net::SocketAddr;
+use rlibbencode::variables::bencode_bytes::BencodeBytes;
+use rlibbencode::variables::bencode_number::BencodeNumber;
+use rlibbencode::variables::bencode_object::{BencodeObject, GetObject, ObjectOptions, PutObject};
+use rlibbencode::variables::inter::bencode_variable::BencodeVariable;
+use crate::messages::inter::message_base::{MessageBase, TID_KEY};
+use crate::messages::inter::message_exception::MessageException;
+use crate::messages::inter::message_type::{MessageType, TYPE_KEY};
+use crate::utils::bencode_utils::{decode_variable, get_encoded};
+use crate::utils::ed25519::{PUBLIC_KEY_LENGTH, SIGNATURE_LENGTH};
+use crate::utils::net::address_types::AddressTypes;
+use crate::utils::net::address_utils::{pack_address, unpack_address};
+use crate::utils::node::Node;
+use crate::utils::node_utils::{pack_nodes, unpack_nodes};
+use crate::utils::uid::{ID_LENGTH, UID};
+use super::inter::method_message_base::MethodMessageBase;
+
+#[derive(Clone, Default)]
+pub struct GetResponse {
+    uid: Option<UID>,
+    tid: Vec<u8>,
+    public: Option<SocketAddr>,
+    destination: Option<SocketAddr>,
+    origin: Option<SocketAddr>,
+    token: Option<Vec<u8>>,
+    nodes: Vec<Node>,
+    value: Option<Vec<u8>>,
+    key: Option<[u8; PUBLIC_KEY_LENGTH]>,
+    signature: Option<[u8; SIGNATURE_LENGTH]>,
+    seq: Option<i64>
+}
+
+impl GetResponse {
+
+    pub fn new(tid: &[u8]) -> Self {
+        Self {
+            tid: tid.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    pub fn set_token(&mut self, token: &[u8]) {
+        self.token = Some(token.to_vec());
+    }
+
+    pub fn get_token(&self) -> Option<&Vec<u8>> {
+        self.token.as_ref()
+    }
+
+    pub fn add_node(&mut self, node: Node) {
+        self.nodes.push(node);
+    }
+
+    pub fn add_nodes(&mut self, nodes: Vec<Node>) {
+        self.nodes.extend(nodes);
+    }
+
+    pub fn has_nodes(&self) -> bool {
+        !self.nodes.is_empty()
+    }
+
+    pub fn get_all_nodes(&self) -> Vec<Node> {
+        self.nodes.clone()
+    }
+
+    pub fn get_all_ipv4_nodes(&self) -> Vec<Node> {
+        self.nodes.iter().filter(|n| n.address.is_ipv4()).copied().collect()
+    }
+
+    pub fn get_all_ipv6_nodes(&self) -> Vec<Node> {
+        self.nodes.iter().filter(|n| n.address.is_ipv6()).copied().collect()
+    }
+
+    pub fn set_value(&mut self, value: &[u8]) {
+        self.value = Some(value.to_vec());
+    }
+
+    pub fn get_value(&self) -> Option<&Vec<u8>> {
+        self.value.as_ref()
+    }
+
+    pub fn has_value(&self) -> bool {
+        self.value.is_some()
+    }
+
+    pub fn set_key(&mut self, key: [u8; PUBLIC_KEY_LENGTH]) {
+        self.key = Some(key);
+    }
+
+    pub fn get_key(&self) -> Option<&[u8; PUBLIC_KEY_LENGTH]> {
+        self.key.as_ref()
+    }
+
+    pub fn set_signature(&mut self, signature: [u8; SIGNATURE_LENGTH]) {
+        self.signature = Some(signature);
+    }
+
+    pub fn get_signature(&self) -> Option<&[u8; SIGNATURE_LENGTH]> {
+        self.signature.as_ref()
+    }
+
+    pub fn set_seq(&mut self, seq: i64) {
+        self.seq = Some(seq);
+    }
+
+    pub fn get_seq(&self) -> Option<i64> {
+        self.seq
+    }
+}
+
+impl MessageBase for GetResponse {
+
+    fn set_uid(&mut self, uid: UID) {
+        self.uid = Some(uid);
+    }
+
+    fn get_uid(&self) -> Option<UID> {
+        self.uid
+    }
+
+    fn set_transaction_id(&mut self, tid: &[u8]) {
+        self.tid = tid.to_vec();
+    }
+
+    fn get_transaction_id(&self) -> &[u8] {
+        &self.tid
+    }
+
+    fn set_public(&mut self, public: SocketAddr) {
+        self.public = Some(public);
+    }
+
+    fn get_public(&self) -> Option<SocketAddr> {
+        self.public
+    }
+
+    fn set_destination(&mut self, destination: SocketAddr) {
+        self.destination = Some(destination);
+    }
+
+    fn get_destination(&self) -> Option<SocketAddr> {
+        self.destination
+    }
+
+    fn set_origin(&mut self, origin: SocketAddr) {
+        self.origin = Some(origin);
+    }
+
+    fn get_origin(&self) -> Option<SocketAddr> {
+        self.origin
+    }
+
+    fn get_type(&self) -> MessageType {
+        MessageType::RspMsg
+    }
+
+    fn encode(&self) -> BencodeObject {
+        let mut ben = BencodeObject::new();
+
+        ben.put(TID_KEY, self.tid.clone());
+        ben.put(TYPE_KEY, self.get_type().rpc_type_name());
+
+        ben.put(self.get_type().inner_key(), BencodeObject::new());
+        ben.get_mut::<BencodeObject>(self.get_type().inner_key()).unwrap().put("id", self.uid.unwrap().bytes());
+
+        if let Some(public) = self.public {
+            ben.put("ip", pack_address(&public));
+        }
+
+        if let Some(token) = &self.token {
+            ben.get_mut::<BencodeObject>(self.get_type().inner_key()).unwrap().put("token", token.clone());
+        }
+
+        if let Some(value) = &self.value {
+            if let Ok(value) = decode_variable(value) {
+                ben.get_mut::<BencodeObject>(self.get_type().inner_key()).unwrap().put("v", value);
+            }
+        }
+
+        if let Some(key) = self.key {
+            ben.get_mut::<BencodeObject>(self.get_type().inner_key()).unwrap().put("k", key);
+        }
+
+        if let Some(signature) = self.signature {
+            ben.get_mut::<BencodeObject>(self.get_type().inner_key()).unwrap().put("sig", signature);
+        }
+
+        if let Some(seq) = self.seq {
+            ben.get_mut::<BencodeObject>(self.get_type().inner_key()).unwrap().put("seq", seq);
+        }
+
+        let nodes = self.get_all_ipv4_nodes();
+        if !nodes.is_empty() {
+            ben.get_mut::<BencodeObject>(self.get_type().inner_key()).unwrap().put("nodes", pack_nodes(nodes, AddressTypes::Ipv4));
+        }
+
+        let nodes = self.get_all_ipv6_nodes();
+        if !nodes.is_empty() {
+            ben.get_mut::<BencodeObject>(self.get_type().inner_key()).unwrap().put("nodes6", pack_nodes(nodes, AddressTypes::Ipv6));
+        }
+
+        ben
+    }
+
+    fn decode(&mut self, ben: &BencodeObject) -> Result<(), MessageException> {
+        if !ben.contains_key(self.get_type().inner_key()) {
+            return Err(MessageException::new("Protocol Error, such as a malformed packet.", 203));
+        }
+
+        let inner = ben.get::<BencodeObject>(self.get_type().inner_key()).unwrap();
+
+        match inner.get::<BencodeBytes>("id") {
+            Some(id) if id.as_bytes().len() == ID_LENGTH => {
+                let mut bid = [0u8; ID_LENGTH];
+                bid.copy_from_slice(&id.as_bytes()[..ID_LENGTH]);
+                self.uid = Some(UID::from(bid));
+            }
+            _ => return Err(MessageException::new("Protocol Error, such as a malformed packet.", 203))
+        }
+
+        if let Some(addr) = ben.get::<BencodeBytes>("ip") {
+            self.public = unpack_address(addr.as_bytes()).ok();
+        }
+
+        if let Some(token) = inner.get::<BencodeBytes>("token") {
+            self.token = Some(token.as_bytes().to_vec());
+        }
+
+        self.value = get_encoded(inner, "v");
+
+        if let Some(key) = inner.get::<BencodeBytes>("k") {
+            self.key = Some(key.as_bytes().try_into()
+                .map_err(|_| MessageException::new("Protocol Error, such as a malformed packet.", 203))?);
+        }
+
+        if let Some(signature) = inner.get::<BencodeBytes>("sig") {
+            self.signature = Some(signature.as_bytes().try_into()
+                .map_err(|_| MessageException::new("Protocol Error, such as a malformed packet.", 203))?);
+        }
+
+        if let Some(seq) = inner.get::<BencodeNumber>("seq") {
+            self.seq = Some(seq.parse::<i64>()
+                .map_err(|_| MessageException::new("Protocol Error, such as a malformed packet.", 203))?);
+        }
+
+        if let Some(nodes) = inner.get::<BencodeBytes>("nodes") {
+            self.nodes.extend(unpack_nodes(nodes.as_bytes(), AddressTypes::Ipv4));
+        }
+
+        if let Some(nodes) = inner.get::<BencodeBytes>("nodes6") {
+            self.nodes.extend(unpack_nodes(nodes.as_bytes(), AddressTypes::Ipv6));
+        }
+
+        Ok(())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl MethodMessageBase for GetResponse {
+
+    fn get_method(&self) -> &str {
+        "get"
+    }
+
+    fn upcast(&self) -> &dyn MessageBase {
+        self
+    }
+
+    fn upcast_mut(&mut self) -> &mut dyn MessageBase {
+        self
+    }
+
+    fn dyn_clone(&self) -> Box<dyn MethodMessageBase> {
+        Box::new(self.clone())
+    }
+}
@@ -2,7 +2,6 @@ use std::any::Any;
 use crate::utils::uid::UID;
 use std::net::SocketAddr;
 use rlibbencode::variables::bencode_object::BencodeObject;
-use crate::kad::server::TID_LENGTH;
 use crate::messages::inter::message_exception::MessageException;
 use crate::messages::inter::message_type::MessageType;
 
@@ -14,9 +13,9 @@ pub trait MessageBase: Send {
 
     fn get_uid(&self) -> Option<UID>;
 
-    fn set_transaction_id(&mut self, tid: [u8; TID_LENGTH]);
+    fn set_transaction_id(&mut self, tid: &[u8]);
 
-    fn get_transaction_id(&self) -> &[u8; TID_LENGTH];
+    fn get_transaction_id(&self) -> &[u8];
 
     fn set_public(&mut self, public_address: SocketAddr);
 
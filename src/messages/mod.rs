@@ -3,4 +3,14 @@ pub mod ping_request;
 pub mod ping_response;
 pub mod find_node_request;
 pub mod find_node_response;
-pub mod error_response;
\ No newline at end of file
+pub mod get_peers_request;
+pub mod get_peers_response;
+pub mod announce_peer_request;
+pub mod announce_peer_response;
+pub mod get_request;
+pub mod get_response;
+pub mod put_request;
+pub mod put_response;
+pub mod error_response;
+pub mod sample_infohashes_request;
+pub mod sample_infohashes_response;
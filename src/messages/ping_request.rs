@@ -2,17 +2,16 @@ use std::any::Any;
 use std::net::SocketAddr;
 use rlibbencode::variables::bencode_bytes::BencodeBytes;
 use rlibbencode::variables::bencode_object::{BencodeObject, GetObject, ObjectOptions, PutObject};
-use crate::kad::server::TID_LENGTH;
 use crate::messages::inter::message_base::{MessageBase, TID_KEY};
 use crate::messages::inter::message_exception::MessageException;
 use crate::messages::inter::message_type::{MessageType, TYPE_KEY};
 use crate::utils::uid::{ID_LENGTH, UID};
 use super::inter::method_message_base::MethodMessageBase;
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct PingRequest {
     uid: Option<UID>,
-    tid: [u8; TID_LENGTH],
+    tid: Vec<u8>,
     public: Option<SocketAddr>,
     destination: Option<SocketAddr>,
     origin: Option<SocketAddr>
@@ -20,27 +19,14 @@ pub struct PingRequest {
 
 impl PingRequest {
 
-    pub fn new(tid: [u8; TID_LENGTH]) -> Self {
+    pub fn new(tid: &[u8]) -> Self {
         Self {
-            tid,
+            tid: tid.to_vec(),
             ..Default::default()
         }
     }
 }
 
-impl Default for PingRequest {
-
-    fn default() -> Self {
-        Self {
-            uid: None,
-            tid: [0u8; TID_LENGTH],
-            public: None,
-            destination: None,
-            origin: None
-        }
-    }
-}
-
 //I WONDER IF WE CAN MACRO THIS SHIT FOR EVERY CLASS...?
 impl MessageBase for PingRequest {
 
@@ -52,11 +38,11 @@ impl MessageBase for PingRequest {
         self.uid
     }
 
-    fn set_transaction_id(&mut self, tid: [u8; TID_LENGTH]) {
-        self.tid = tid;
+    fn set_transaction_id(&mut self, tid: &[u8]) {
+        self.tid = tid.to_vec();
     }
 
-    fn get_transaction_id(&self) -> &[u8; TID_LENGTH] {
+    fn get_transaction_id(&self) -> &[u8] {
         &self.tid
     }
 
@@ -92,7 +78,6 @@ impl MessageBase for PingRequest {
         let mut ben = BencodeObject::new();
 
         ben.put(TID_KEY, self.tid.clone());
-        ben.put("v", "1.0");
         ben.put(TYPE_KEY, self.get_type().rpc_type_name());
 
         ben.put(self.get_type().rpc_type_name(), self.get_method());
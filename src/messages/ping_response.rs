@@ -2,7 +2,6 @@ use std::any::Any;
 use std::net::SocketAddr;
 use rlibbencode::variables::bencode_bytes::BencodeBytes;
 use rlibbencode::variables::bencode_object::{BencodeObject, GetObject, ObjectOptions, PutObject};
-use crate::kad::server::TID_LENGTH;
 use crate::messages::inter::message_base::{MessageBase, TID_KEY};
 use crate::messages::inter::message_exception::MessageException;
 use crate::messages::inter::message_type::{MessageType, TYPE_KEY};
@@ -10,10 +9,10 @@ use crate::utils::net::address_utils::{pack_address, unpack_address};
 use crate::utils::uid::{ID_LENGTH, UID};
 use super::inter::method_message_base::MethodMessageBase;
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct PingResponse {
     uid: Option<UID>,
-    tid: [u8; TID_LENGTH],
+    tid: Vec<u8>,
     public: Option<SocketAddr>,
     destination: Option<SocketAddr>,
     origin: Option<SocketAddr>
@@ -21,27 +20,14 @@ pub struct PingResponse {
 
 impl PingResponse {
 
-    pub fn new(tid: [u8; TID_LENGTH]) -> Self {
+    pub fn new(tid: &[u8]) -> Self {
         Self {
-            tid,
+            tid: tid.to_vec(),
             ..Default::default()
         }
     }
 }
 
-impl Default for PingResponse {
-
-    fn default() -> Self {
-        Self {
-            uid: None,
-            tid: [0u8; TID_LENGTH],
-            public: None,
-            destination: None,
-            origin: None
-        }
-    }
-}
-
 //I WONDER IF WE CAN MACRO THIS SHIT FOR EVERY CLASS...?
 impl MessageBase for PingResponse {
 
@@ -53,11 +39,11 @@ impl MessageBase for PingResponse {
         self.uid
     }
 
-    fn set_transaction_id(&mut self, tid: [u8; TID_LENGTH]) {
-        self.tid = tid;
+    fn set_transaction_id(&mut self, tid: &[u8]) {
+        self.tid = tid.to_vec();
     }
 
-    fn get_transaction_id(&self) -> &[u8; TID_LENGTH] {
+    fn get_transaction_id(&self) -> &[u8] {
         &self.tid
     }
 
@@ -93,7 +79,6 @@ impl MessageBase for PingResponse {
         let mut ben = BencodeObject::new();
 
         ben.put(TID_KEY, self.tid.clone());
-        ben.put("v", "1.0");
         ben.put(TYPE_KEY, self.get_type().rpc_type_name());
 
         ben.put(self.get_type().inner_key(), BencodeObject::new());
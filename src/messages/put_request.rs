@@ -0,0 +1,278 @@
+use std::any::Any;
+use std::net::SocketAddr;
+use rlibbencode::variables::bencode_bytes::BencodeBytes;
+use rlibbencode::variables::bencode_number::BencodeNumber;
+use rlibbencode::variables::bencode_object::{BencodeObject, GetObject, ObjectOptions, PutObject};
+use rlibbencode::variables::inter::bencode_variable::BencodeVariable;
+use crate::messages::inter::message_base::{MessageBase, TID_KEY};
+use crate::messages::inter::message_exception::MessageException;
+use crate::messages::inter::message_type::{MessageType, TYPE_KEY};
+use crate::utils::bencode_utils::{decode_variable, get_encoded};
+use crate::utils::ed25519::{PUBLIC_KEY_LENGTH, SIGNATURE_LENGTH};
+use crate::utils::uid::{ID_LENGTH, UID};
+use super::inter::method_message_base::MethodMessageBase;
+
+#[derive(Clone, Default)]
+pub struct PutRequest {
+    uid: Option<UID>,
+    tid: Vec<u8>,
+    public: Option<SocketAddr>,
+    destination: Option<SocketAddr>,
+    origin: Option<SocketAddr>,
+    token: Option<Vec<u8>>,
+    value: Option<Vec<u8>>,
+    key: Option<[u8; PUBLIC_KEY_LENGTH]>,
+    salt: Option<Vec<u8>>,
+    signature: Option<[u8; SIGNATURE_LENGTH]>,
+    seq: Option<i64>,
+    cas: Option<i64>
+}
+
+impl PutRequest {
+
+    pub fn new(tid: &[u8]) -> Self {
+        Self {
+            tid: tid.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    pub fn set_token(&mut self, token: &[u8]) {
+        self.token = Some(token.to_vec());
+    }
+
+    pub fn get_token(&self) -> Option<&Vec<u8>> {
+        self.token.as_ref()
+    }
+
+    pub fn set_value(&mut self, value: &[u8]) {
+        self.value = Some(value.to_vec());
+    }
+
+    pub fn get_value(&self) -> Option<&Vec<u8>> {
+        self.value.as_ref()
+    }
+
+    pub fn set_key(&mut self, key: [u8; PUBLIC_KEY_LENGTH]) {
+        self.key = Some(key);
+    }
+
+    pub fn get_key(&self) -> Option<&[u8; PUBLIC_KEY_LENGTH]> {
+        self.key.as_ref()
+    }
+
+    pub fn is_mutable(&self) -> bool {
+        self.key.is_some()
+    }
+
+    pub fn set_salt(&mut self, salt: &[u8]) {
+        self.salt = Some(salt.to_vec());
+    }
+
+    pub fn get_salt(&self) -> Option<&Vec<u8>> {
+        self.salt.as_ref()
+    }
+
+    pub fn set_signature(&mut self, signature: [u8; SIGNATURE_LENGTH]) {
+        self.signature = Some(signature);
+    }
+
+    pub fn get_signature(&self) -> Option<&[u8; SIGNATURE_LENGTH]> {
+        self.signature.as_ref()
+    }
+
+    pub fn set_seq(&mut self, seq: i64) {
+        self.seq = Some(seq);
+    }
+
+    pub fn get_seq(&self) -> Option<i64> {
+        self.seq
+    }
+
+    pub fn set_cas(&mut self, cas: i64) {
+        self.cas = Some(cas);
+    }
+
+    pub fn get_cas(&self) -> Option<i64> {
+        self.cas
+    }
+}
+
+impl MessageBase for PutRequest {
+
+    fn set_uid(&mut self, uid: UID) {
+        self.uid = Some(uid);
+    }
+
+    fn get_uid(&self) -> Option<UID> {
+        self.uid
+    }
+
+    fn set_transaction_id(&mut self, tid: &[u8]) {
+        self.tid = tid.to_vec();
+    }
+
+    fn get_transaction_id(&self) -> &[u8] {
+        &self.tid
+    }
+
+    fn set_public(&mut self, public: SocketAddr) {
+        self.public = Some(public);
+    }
+
+    fn get_public(&self) -> Option<SocketAddr> {
+        self.public
+    }
+
+    fn set_destination(&mut self, destination: SocketAddr) {
+        self.destination = Some(destination);
+    }
+
+    fn get_destination(&self) -> Option<SocketAddr> {
+        self.destination
+    }
+
+    fn set_origin(&mut self, origin: SocketAddr) {
+        self.origin = Some(origin);
+    }
+
+    fn get_origin(&self) -> Option<SocketAddr> {
+        self.origin
+    }
+
+    fn get_type(&self) -> MessageType {
+        MessageType::ReqMsg
+    }
+
+    fn encode(&self) -> BencodeObject {
+        let mut ben = BencodeObject::new();
+
+        ben.put(TID_KEY, self.tid.clone());
+        ben.put(TYPE_KEY, self.get_type().rpc_type_name());
+
+        ben.put(self.get_type().rpc_type_name(), self.get_method());
+        ben.put(self.get_type().inner_key(), BencodeObject::new());
+
+        let inner = ben.get_mut::<BencodeObject>(self.get_type().inner_key()).unwrap();
+        inner.put("id", self.uid.unwrap().bytes());
+
+        if let Some(token) = &self.token {
+            inner.put("token", token.clone());
+        }
+
+        if let Some(value) = &self.value {
+            if let Ok(value) = decode_variable(value) {
+                inner.put("v", value);
+            }
+        }
+
+        if let Some(key) = self.key {
+            inner.put("k", key);
+        }
+
+        if let Some(salt) = &self.salt {
+            inner.put("salt", salt.clone());
+        }
+
+        if let Some(signature) = self.signature {
+            inner.put("sig", signature);
+        }
+
+        if let Some(seq) = self.seq {
+            inner.put("seq", seq);
+        }
+
+        if let Some(cas) = self.cas {
+            inner.put("cas", cas);
+        }
+
+        ben
+    }
+
+    fn decode(&mut self, ben: &BencodeObject) -> Result<(), MessageException> {
+        if !ben.contains_key(self.get_type().inner_key()) {
+            return Err(MessageException::new("Protocol Error, such as a malformed packet.", 203));
+        }
+
+        let inner = ben.get::<BencodeObject>(self.get_type().inner_key()).unwrap();
+
+        match inner.get::<BencodeBytes>("id") {
+            Some(id) if id.as_bytes().len() == ID_LENGTH => {
+                let mut bid = [0u8; ID_LENGTH];
+                bid.copy_from_slice(&id.as_bytes()[..ID_LENGTH]);
+                self.uid = Some(UID::from(bid));
+            }
+            _ => return Err(MessageException::new("Protocol Error, such as a malformed packet.", 203))
+        }
+
+        match inner.get::<BencodeBytes>("token") {
+            Some(token) => self.token = Some(token.as_bytes().to_vec()),
+            _ => return Err(MessageException::new("Protocol Error, such as a malformed packet.", 203))
+        }
+
+        match get_encoded(inner, "v") {
+            Some(value) => self.value = Some(value),
+            _ => return Err(MessageException::new("Protocol Error, such as a malformed packet.", 203))
+        }
+
+        //MUTABLE ITEMS MUST CARRY THE KEY, SIGNATURE AND SEQ TOGETHER
+        if let Some(key) = inner.get::<BencodeBytes>("k") {
+            self.key = Some(key.as_bytes().try_into()
+                .map_err(|_| MessageException::new("Protocol Error, such as a malformed packet.", 203))?);
+
+            match inner.get::<BencodeBytes>("sig") {
+                Some(signature) => {
+                    self.signature = Some(signature.as_bytes().try_into()
+                        .map_err(|_| MessageException::new("Protocol Error, such as a malformed packet.", 203))?);
+                }
+                _ => return Err(MessageException::new("Protocol Error, such as a malformed packet.", 203))
+            }
+
+            match inner.get::<BencodeNumber>("seq") {
+                Some(seq) => {
+                    self.seq = Some(seq.parse::<i64>()
+                        .map_err(|_| MessageException::new("Protocol Error, such as a malformed packet.", 203))?);
+                }
+                _ => return Err(MessageException::new("Protocol Error, such as a malformed packet.", 203))
+            }
+
+            if let Some(salt) = inner.get::<BencodeBytes>("salt") {
+                self.salt = Some(salt.as_bytes().to_vec());
+            }
+
+            if let Some(cas) = inner.get::<BencodeNumber>("cas") {
+                self.cas = Some(cas.parse::<i64>()
+                    .map_err(|_| MessageException::new("Protocol Error, such as a malformed packet.", 203))?);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl MethodMessageBase for PutRequest {
+
+    fn get_method(&self) -> &str {
+        "put"
+    }
+
+    fn upcast(&self) -> &dyn MessageBase {
+        self
+    }
+
+    fn upcast_mut(&mut self) -> &mut dyn MessageBase {
+        self
+    }
+
+    fn dyn_clone(&self) -> Box<dyn MethodMessageBase> {
+        Box::new(self.clone())
+    }
+}
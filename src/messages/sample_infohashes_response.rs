@@ -0,0 +1,256 @@
+use std::any::Any;
+use std::net::SocketAddr;
+use rlibbencode::variables::bencode_bytes::BencodeBytes;
+use rlibbencode::variables::bencode_number::BencodeNumber;
+use rlibbencode::variables::bencode_object::{BencodeObject, GetObject, ObjectOptions, PutObject};
+use rlibbencode::variables::inter::bencode_variable::BencodeVariable;
+use crate::messages::inter::message_base::{MessageBase, TID_KEY};
+use crate::messages::inter::message_exception::MessageException;
+use crate::messages::inter::message_type::{MessageType, TYPE_KEY};
+use crate::utils::net::address_types::AddressTypes;
+use crate::utils::net::address_utils::{pack_address, unpack_address};
+use crate::utils::node::Node;
+use crate::utils::node_utils::{pack_nodes, unpack_nodes};
+use crate::utils::uid::{ID_LENGTH, UID};
+use super::inter::method_message_base::MethodMessageBase;
+
+#[derive(Clone, Default)]
+pub struct SampleInfohashesResponse {
+    uid: Option<UID>,
+    tid: Vec<u8>,
+    public: Option<SocketAddr>,
+    destination: Option<SocketAddr>,
+    origin: Option<SocketAddr>,
+    interval: u32,
+    num: u32,
+    nodes: Vec<Node>,
+    samples: Vec<UID>
+}
+
+impl SampleInfohashesResponse {
+
+    pub fn new(tid: &[u8]) -> Self {
+        Self {
+            tid: tid.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    pub fn set_interval(&mut self, interval: u32) {
+        self.interval = interval;
+    }
+
+    pub fn get_interval(&self) -> u32 {
+        self.interval
+    }
+
+    pub fn set_num(&mut self, num: u32) {
+        self.num = num;
+    }
+
+    pub fn get_num(&self) -> u32 {
+        self.num
+    }
+
+    pub fn add_node(&mut self, node: Node) {
+        self.nodes.push(node);
+    }
+
+    pub fn add_nodes(&mut self, nodes: Vec<Node>) {
+        self.nodes.extend(nodes);
+    }
+
+    pub fn has_nodes(&self) -> bool {
+        !self.nodes.is_empty()
+    }
+
+    pub fn get_all_nodes(&self) -> Vec<Node> {
+        self.nodes.clone()
+    }
+
+    pub fn get_all_ipv4_nodes(&self) -> Vec<Node> {
+        self.nodes.iter().filter(|n| n.address.is_ipv4()).copied().collect()
+    }
+
+    pub fn get_all_ipv6_nodes(&self) -> Vec<Node> {
+        self.nodes.iter().filter(|n| n.address.is_ipv6()).copied().collect()
+    }
+
+    pub fn add_sample(&mut self, sample: UID) {
+        self.samples.push(sample);
+    }
+
+    pub fn add_samples(&mut self, samples: Vec<UID>) {
+        self.samples.extend(samples);
+    }
+
+    pub fn has_samples(&self) -> bool {
+        !self.samples.is_empty()
+    }
+
+    pub fn get_samples(&self) -> Vec<UID> {
+        self.samples.clone()
+    }
+}
+
+impl MessageBase for SampleInfohashesResponse {
+
+    fn set_uid(&mut self, uid: UID) {
+        self.uid = Some(uid);
+    }
+
+    fn get_uid(&self) -> Option<UID> {
+        self.uid
+    }
+
+    fn set_transaction_id(&mut self, tid: &[u8]) {
+        self.tid = tid.to_vec();
+    }
+
+    fn get_transaction_id(&self) -> &[u8] {
+        &self.tid
+    }
+
+    fn set_public(&mut self, public: SocketAddr) {
+        self.public = Some(public);
+    }
+
+    fn get_public(&self) -> Option<SocketAddr> {
+        self.public
+    }
+
+    fn set_destination(&mut self, destination: SocketAddr) {
+        self.destination = Some(destination);
+    }
+
+    fn get_destination(&self) -> Option<SocketAddr> {
+        self.destination
+    }
+
+    fn set_origin(&mut self, origin: SocketAddr) {
+        self.origin = Some(origin);
+    }
+
+    fn get_origin(&self) -> Option<SocketAddr> {
+        self.origin
+    }
+
+    fn get_type(&self) -> MessageType {
+        MessageType::RspMsg
+    }
+
+    fn encode(&self) -> BencodeObject {
+        let mut ben = BencodeObject::new();
+
+        ben.put(TID_KEY, self.tid.clone());
+        ben.put(TYPE_KEY, self.get_type().rpc_type_name());
+
+        ben.put(self.get_type().inner_key(), BencodeObject::new());
+        ben.get_mut::<BencodeObject>(self.get_type().inner_key()).unwrap().put("id", self.uid.unwrap().bytes());
+
+        if let Some(public) = self.public {
+            ben.put("ip", pack_address(&public));
+        }
+
+        ben.get_mut::<BencodeObject>(self.get_type().inner_key()).unwrap().put("interval", self.interval);
+        ben.get_mut::<BencodeObject>(self.get_type().inner_key()).unwrap().put("num", self.num);
+
+        let mut samples = Vec::with_capacity(self.samples.len()*ID_LENGTH);
+        for sample in &self.samples {
+            samples.extend_from_slice(&sample.bytes());
+        }
+        ben.get_mut::<BencodeObject>(self.get_type().inner_key()).unwrap().put("samples", samples);
+
+        let nodes = self.get_all_ipv4_nodes();
+        if !nodes.is_empty() {
+            ben.get_mut::<BencodeObject>(self.get_type().inner_key()).unwrap().put("nodes", pack_nodes(nodes, AddressTypes::Ipv4));
+        }
+
+        let nodes = self.get_all_ipv6_nodes();
+        if !nodes.is_empty() {
+            ben.get_mut::<BencodeObject>(self.get_type().inner_key()).unwrap().put("nodes6", pack_nodes(nodes, AddressTypes::Ipv6));
+        }
+
+        ben
+    }
+
+    fn decode(&mut self, ben: &BencodeObject) -> Result<(), MessageException> {
+        if !ben.contains_key(self.get_type().inner_key()) {
+            return Err(MessageException::new("Protocol Error, such as a malformed packet.", 203));
+        }
+
+        let inner = ben.get::<BencodeObject>(self.get_type().inner_key()).unwrap();
+
+        match inner.get::<BencodeBytes>("id") {
+            Some(id) if id.as_bytes().len() == ID_LENGTH => {
+                let mut bid = [0u8; ID_LENGTH];
+                bid.copy_from_slice(&id.as_bytes()[..ID_LENGTH]);
+                self.uid = Some(UID::from(bid));
+            }
+            _ => return Err(MessageException::new("Protocol Error, such as a malformed packet.", 203))
+        }
+
+        if let Some(addr) = ben.get::<BencodeBytes>("ip") {
+            self.public = unpack_address(addr.as_bytes()).ok();
+        }
+
+        if let Some(interval) = inner.get::<BencodeNumber>("interval") {
+            self.interval = interval.parse::<u32>()
+                .map_err(|_| MessageException::new("Protocol Error, such as a malformed packet.", 203))?;
+        }
+
+        if let Some(num) = inner.get::<BencodeNumber>("num") {
+            self.num = num.parse::<u32>()
+                .map_err(|_| MessageException::new("Protocol Error, such as a malformed packet.", 203))?;
+        }
+
+        match inner.get::<BencodeBytes>("samples") {
+            Some(samples) if samples.as_bytes().len() % ID_LENGTH == 0 => {
+                for sample in samples.as_bytes().chunks_exact(ID_LENGTH) {
+                    let mut bid = [0u8; ID_LENGTH];
+                    bid.copy_from_slice(sample);
+                    self.samples.push(UID::from(bid));
+                }
+            }
+            Some(_) => return Err(MessageException::new("Protocol Error, such as a malformed packet.", 203)),
+            None => {}
+        }
+
+        if let Some(nodes) = inner.get::<BencodeBytes>("nodes") {
+            self.nodes.extend(unpack_nodes(nodes.as_bytes(), AddressTypes::Ipv4));
+        }
+
+        if let Some(nodes) = inner.get::<BencodeBytes>("nodes6") {
+            self.nodes.extend(unpack_nodes(nodes.as_bytes(), AddressTypes::Ipv6));
+        }
+
+        Ok(())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl MethodMessageBase for SampleInfohashesResponse {
+
+    fn get_method(&self) -> &str {
+        "sample_infohashes"
+    }
+
+    fn upcast(&self) -> &dyn MessageBase {
+        self
+    }
+
+    fn upcast_mut(&mut self) -> &mut dyn MessageBase {
+        self
+    }
+
+    fn dyn_clone(&self) -> Box<dyn MethodMessageBase> {
+        Box::new(self.clone())
+    }
+}
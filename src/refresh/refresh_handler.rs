@@ -1,15 +1,61 @@
-use std::sync::Arc;
+use std::sync::{Arc, Condvar, Mutex};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::thread;
-use std::thread::sleep;
-use std::time::Duration;
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use crate::refresh::tasks::inter::task::Task;
 
+pub const DEFAULT_REFRESH_TIME: u64 = 3600000;
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct TaskHandle(u64);
+
+#[derive(Copy, Clone)]
+enum Interval {
+    Refresh,
+    Every(u64),
+    Once
+}
+
+struct ScheduledTask {
+    handle: TaskHandle,
+    task: Box<dyn Task>,
+    delay: Option<u64>,
+    interval: Interval,
+    next_run: u128
+}
+
+impl ScheduledTask {
+
+    //WITHOUT A DELAY THE FIRST RUN IS ONE INTERVAL AFTER THE START
+    fn schedule(&mut self, now: u128, refresh_time: u64) {
+        let delay = self.delay.unwrap_or(match self.interval {
+            Interval::Refresh => refresh_time,
+            Interval::Every(interval) => interval,
+            Interval::Once => 0
+        });
+
+        self.next_run = now+delay as u128;
+    }
+}
+
+struct Shared {
+    tasks: Mutex<Vec<ScheduledTask>>,
+    condvar: Condvar,
+    #[cfg(feature = "tokio")]
+    notify: tokio::sync::Notify
+}
+
 pub struct RefreshHandler {
     //pub(crate) kademlia: Option<Box<dyn KademliaBase>>,
-    tasks: Vec<Box<dyn Task>>,
+    shared: Arc<Shared>,
+    next_handle: u64,
     refresh_time: Arc<AtomicU64>,
-    running: Arc<AtomicBool>
+    running: Arc<AtomicBool>,
+    stopped: bool,
+    handle: Option<JoinHandle<()>>,
+    #[cfg(feature = "tokio")]
+    task: Option<tokio::task::JoinHandle<()>>
 }
 
 impl RefreshHandler {
@@ -17,9 +63,19 @@ impl RefreshHandler {
     pub fn new() -> Self {
         Self {
             //kademlia: None,
-            tasks: Vec::new(),
-            refresh_time: Arc::new(AtomicU64::new(3600000)),
-            running: Arc::new(AtomicBool::new(false))
+            shared: Arc::new(Shared {
+                tasks: Mutex::new(Vec::new()),
+                condvar: Condvar::new(),
+                #[cfg(feature = "tokio")]
+                notify: tokio::sync::Notify::new()
+            }),
+            next_handle: 0,
+            refresh_time: Arc::new(AtomicU64::new(DEFAULT_REFRESH_TIME)),
+            running: Arc::new(AtomicBool::new(false)),
+            stopped: false,
+            handle: None,
+            #[cfg(feature = "tokio")]
+            task: None
         }
     }
 
@@ -27,35 +83,109 @@ impl RefreshHandler {
         self.running.load(Ordering::Relaxed)
     }
 
+    pub fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+
     //- we should probably just static the damn handler at this point....
-    pub fn start(&self) {
+    pub fn start(&mut self) {
+        self.stopped = false;
+
         if self.is_running() {
             //panic or something...
             return;
         }
 
-        self.running.store(true, Ordering::Relaxed);
+        //EVERY START GETS ITS OWN FLAG SO A LOOP THAT WASNT JOINED CANT BE REVIVED BY A RESTART
+        self.running = Arc::new(AtomicBool::new(true));
+
+        {
+            let now = now();
+            let refresh_time = self.get_refresh_time();
+            self.shared.tasks.lock().unwrap().iter_mut().for_each(|task| task.schedule(now, refresh_time));
+        }
+
+        //WITH THE TOKIO FEATURE THE TASKS ARE DRIVEN BY TOKIO TIMERS WHEN STARTED INSIDE A RUNTIME
+        #[cfg(feature = "tokio")]
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let shared = Arc::clone(&self.shared);
+            let refresh_time = Arc::clone(&self.refresh_time);
+            let running = Arc::clone(&self.running);
+            self.task = Some(runtime.spawn(async move {
+                while running.load(Ordering::Relaxed) {
+                    let (due, wait) = take_due(&mut shared.tasks.lock().unwrap(), refresh_time.load(Ordering::SeqCst));
+
+                    if due.is_empty() {
+                        tokio::select! {
+                            _ = tokio::time::sleep(Duration::from_millis(wait)) => {},
+                            _ = shared.notify.notified() => {}
+                        }
+                        continue;
+                    }
+
+                    execute(due, &running);
+                }
+            }));
+            return;
+        }
 
-        let handle = thread::spawn({
-            let tasks = self.tasks.clone();
+        self.handle = Some(thread::spawn({
+            let shared = Arc::clone(&self.shared);
             let refresh_time = Arc::clone(&self.refresh_time);
             let running = Arc::clone(&self.running);
             move || {
+                let mut tasks = shared.tasks.lock().unwrap();
+
                 while running.load(Ordering::Relaxed) { //self.is_running()
-                    sleep(Duration::from_millis(refresh_time.load(Ordering::SeqCst)));
+                    let (due, wait) = take_due(&mut tasks, refresh_time.load(Ordering::SeqCst));
 
-                    for task in &tasks {
-                        task.execute();
+                    if due.is_empty() {
+                        tasks = shared.condvar.wait_timeout(tasks, Duration::from_millis(wait)).unwrap().0;
+                        continue;
                     }
+
+                    //TASKS RUN WITHOUT THE LOCK SO THEY CAN ADD OR REMOVE OPERATIONS THEMSELVES
+                    drop(tasks);
+                    execute(due, &running);
+                    tasks = shared.tasks.lock().unwrap();
                 }
             }
-        });
+        }));
+    }
 
-        //handle.join().unwrap();
+    //STARTS THE HANDLER FROM INCOMING TRAFFIC, A HANDLER THAT WAS STOPPED STAYS STOPPED UNTIL reset OR start
+    pub fn resume(&mut self) {
+        if !self.stopped && !self.is_running() {
+            self.start();
+        }
+    }
+
+    //CLEARS A PREVIOUS stop SO TRAFFIC CAN START THE HANDLER AGAIN
+    pub fn reset(&mut self) {
+        self.stopped = false;
     }
 
-    pub fn stop(&self) {
+    //INTERRUPTS THE SLEEP AND WAITS FOR THE TASK THAT IS RUNNING TO FINISH
+    pub fn stop(&mut self) {
+        self.stopped = true;
         self.running.store(false, Ordering::Relaxed);
+
+        {
+            let _tasks = self.shared.tasks.lock().unwrap();
+            self.wake();
+        }
+
+        #[cfg(feature = "tokio")]
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+
+        if let Some(handle) = self.handle.take() {
+            //A TASK THAT STOPS THE HANDLER CANT WAIT ON ITS OWN THREAD
+            if handle.thread().id() != thread::current().id() {
+                handle.join().ok();
+            }
+        }
     }
 
     pub fn get_refresh_time(&self) -> u64 {
@@ -66,7 +196,176 @@ impl RefreshHandler {
         self.refresh_time.store(refresh_time, Ordering::SeqCst);
     }
 
-    pub fn add_operation(&mut self, task: Box<dyn Task>) {
-        self.tasks.push(task);
+    //RUNS EVERY refresh_time
+    pub fn add_operation(&mut self, task: Box<dyn Task>) -> TaskHandle {
+        self.schedule(task, None, Interval::Refresh)
+    }
+
+    //RUNS ON ITS OWN INTERVAL INSTEAD OF refresh_time
+    pub fn add_operation_with_interval(&mut self, task: Box<dyn Task>, interval: u64) -> TaskHandle {
+        self.schedule(task, None, Interval::Every(interval.max(1)))
+    }
+
+    pub fn add_operation_with_delay(&mut self, task: Box<dyn Task>, delay: u64, interval: u64) -> TaskHandle {
+        self.schedule(task, Some(delay), Interval::Every(interval.max(1)))
+    }
+
+    //RUNS ONCE AFTER delay AND IS THEN REMOVED
+    pub fn add_one_shot_operation(&mut self, task: Box<dyn Task>, delay: u64) -> TaskHandle {
+        self.schedule(task, Some(delay), Interval::Once)
+    }
+
+    pub fn remove_operation(&mut self, handle: TaskHandle) -> bool {
+        let mut tasks = self.shared.tasks.lock().unwrap();
+        let len = tasks.len();
+        tasks.retain(|task| task.handle != handle);
+
+        if tasks.len() == len {
+            return false;
+        }
+
+        self.wake();
+        true
+    }
+
+    fn schedule(&mut self, task: Box<dyn Task>, delay: Option<u64>, interval: Interval) -> TaskHandle {
+        let handle = TaskHandle(self.next_handle);
+        self.next_handle += 1;
+
+        let mut task = ScheduledTask {
+            handle,
+            task,
+            delay,
+            interval,
+            next_run: 0
+        };
+        task.schedule(now(), self.get_refresh_time());
+
+        self.shared.tasks.lock().unwrap().push(task);
+        self.wake();
+        handle
+    }
+
+    //CALLED WITH THE TASK LIST CHANGED, THE LOOP RECOMPUTES ITS NEXT WAKE UP
+    fn wake(&self) {
+        self.shared.condvar.notify_all();
+
+        #[cfg(feature = "tokio")]
+        self.shared.notify.notify_one();
+    }
+}
+
+fn now() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_millis()
+}
+
+//RETURNS THE TASKS THAT ARE DUE AND HOW LONG TO SLEEP UNTIL THE NEXT ONE, ONE SHOT TASKS ARE REMOVED ONCE TAKEN
+fn take_due(tasks: &mut Vec<ScheduledTask>, refresh_time: u64) -> (Vec<Box<dyn Task>>, u64) {
+    let now = now();
+    let mut due = Vec::new();
+
+    tasks.retain_mut(|task| {
+        if task.next_run > now {
+            return true;
+        }
+
+        due.push(task.task.clone());
+
+        match task.interval {
+            Interval::Refresh => task.next_run = now+refresh_time as u128,
+            Interval::Every(interval) => task.next_run = now+interval as u128,
+            Interval::Once => return false
+        }
+
+        true
+    });
+
+    let wait = tasks.iter().map(|task| task.next_run.saturating_sub(now) as u64).min().unwrap_or(refresh_time);
+    (due, wait)
+}
+
+fn execute(tasks: Vec<Box<dyn Task>>, running: &AtomicBool) {
+    for task in tasks {
+        if !running.load(Ordering::Relaxed) {
+            return;
+        }
+
+        if let Err(e) = task.execute() {
+            println!("{}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::io;
+    use std::sync::atomic::AtomicUsize;
+    use std::thread::sleep;
+    use std::time::Instant;
+    use super::*;
+
+    #[derive(Clone)]
+    struct CountTask {
+        count: Arc<AtomicUsize>
+    }
+
+    impl Task for CountTask {
+
+        fn execute(&self) -> io::Result<()> {
+            self.count.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Task> {
+            Box::new(self.clone())
+        }
+    }
+
+    #[test]
+    fn task_schedules() {
+        let counts: Vec<Arc<AtomicUsize>> = (0..3).map(|_| Arc::new(AtomicUsize::new(0))).collect();
+        let mut handler = RefreshHandler::new();
+
+        handler.add_one_shot_operation(Box::new(CountTask { count: counts[0].clone() }), 0);
+        let every = handler.add_operation_with_delay(Box::new(CountTask { count: counts[1].clone() }), 0, 10);
+        let removed = handler.add_operation_with_interval(Box::new(CountTask { count: counts[2].clone() }), 10);
+        assert!(handler.remove_operation(removed));
+        assert!(!handler.remove_operation(removed));
+
+        handler.start();
+        sleep(Duration::from_millis(200));
+        assert!(handler.remove_operation(every));
+
+        //ONLY THE HOURLY DEFAULT IS LEFT TO WAIT ON, STOP HAS TO CUT THAT SLEEP SHORT
+        handler.add_operation(Box::new(CountTask { count: counts[2].clone() }));
+        let stopped = Instant::now();
+        handler.stop();
+        assert!(stopped.elapsed() < Duration::from_secs(1));
+        assert!(!handler.is_running());
+
+        assert_eq!(counts[0].load(Ordering::SeqCst), 1);
+        assert!(counts[1].load(Ordering::SeqCst) > 1);
+        assert_eq!(counts[2].load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn sticky_stop() {
+        let mut handler = RefreshHandler::new();
+        handler.resume();
+        assert!(handler.is_running());
+
+        //TRAFFIC THAT ARRIVES AFTER THE STOP CANT REVIVE IT
+        handler.stop();
+        handler.resume();
+        assert!(!handler.is_running() && handler.is_stopped());
+
+        handler.reset();
+        handler.resume();
+        assert!(handler.is_running());
+        handler.stop();
     }
 }
@@ -1,6 +1,9 @@
+use std::io;
+
 pub trait Task: Send {
 
-    fn execute(&self);
+    //AN ERROR IS ONLY REPORTED, THE TASK STAYS SCHEDULED
+    fn execute(&self) -> io::Result<()>;
 
     fn clone_dyn(&self) -> Box<dyn Task>;
 }
@@ -1,3 +1,5 @@
 pub mod inter;
 pub mod bucket_refresh_task;
 pub mod stale_refresh_task;
+pub mod save_state_task;
+pub mod peer_expire_task;
\ No newline at end of file
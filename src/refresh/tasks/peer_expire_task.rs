@@ -0,0 +1,38 @@
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::storage::peer_store::PeerStore;
+use super::inter::task::Task;
+
+pub const PEER_EXPIRE_INTERVAL: u64 = 300000;
+
+#[derive(Clone)]
+pub struct PeerExpireTask {
+    peer_store: Arc<Mutex<PeerStore>>
+}
+
+impl PeerExpireTask {
+
+    pub fn new(peer_store: &Arc<Mutex<PeerStore>>) -> Self {
+        Self {
+            peer_store: peer_store.clone()
+        }
+    }
+}
+
+impl Task for PeerExpireTask {
+
+    fn execute(&self) -> io::Result<()> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_millis();
+
+        self.peer_store.lock().unwrap().remove_expired(now);
+        Ok(())
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Task> {
+        Box::new(self.clone())
+    }
+}
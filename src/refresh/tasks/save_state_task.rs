@@ -0,0 +1,34 @@
+use std::io;
+use crate::kad::kademlia_base::KademliaBase;
+use crate::routing::routing_state::RoutingState;
+use super::inter::task::Task;
+
+#[derive(Clone)]
+pub struct SaveStateTask {
+    kademlia: Box<dyn KademliaBase>
+}
+
+impl SaveStateTask {
+
+    pub fn new(kademlia: &dyn KademliaBase) -> Self {
+        Self {
+            kademlia: kademlia.clone_dyn()
+        }
+    }
+}
+
+impl Task for SaveStateTask {
+
+    fn execute(&self) -> io::Result<()> {
+        if let Some(state_file) = self.kademlia.get_config().get_state_file() {
+            let state = RoutingState::from_routing_tables(self.kademlia.get_routing_table(), self.kademlia.get_routing_table6());
+            state.save(state_file)?;
+        }
+
+        Ok(())
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Task> {
+        Box::new(self.clone())
+    }
+}
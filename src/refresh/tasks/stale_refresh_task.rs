@@ -1,3 +1,4 @@
+use std::io;
 use crate::kad::kademlia_base::KademliaBase;
 use crate::messages::inter::message_base::MessageBase;
 use crate::messages::ping_request::PingRequest;
@@ -20,16 +21,35 @@ impl StaleRefreshTask {
 
 impl Task for StaleRefreshTask {
 
-    fn execute(&self) {
+    fn execute(&self) -> io::Result<()> {
         println!("StaleRefresh");
-        let listener = Box::new(PingResponseListener::new(self.kademlia.get_routing_table().clone()));
-        let nodes = self.kademlia.get_routing_table().lock().unwrap().all_unqueried_nodes();
+        let mut routing_tables = Vec::new();
+        if self.kademlia.get_server().lock().unwrap().has_ipv4() {
+            routing_tables.push(self.kademlia.get_routing_table().clone());
+        }
 
-        for node in nodes {
-            let mut request = PingRequest::default();
-            request.set_destination(node.address);
-            self.kademlia.get_server().lock().unwrap().send_with_node_callback(&mut request, node, listener.clone()).unwrap();
+        if self.kademlia.get_server().lock().unwrap().has_ipv6() {
+            routing_tables.push(self.kademlia.get_routing_table6().clone());
         }
+
+        for routing_table in routing_tables {
+            let listener = Box::new(PingResponseListener::new(routing_table.clone()));
+            let nodes = routing_table.lock().unwrap().all_unqueried_nodes();
+
+            for node in nodes {
+                let mut request = PingRequest::default();
+                request.set_destination(node.address);
+
+                //ONE NODE THAT CANT BE PINGED SHOULDNT SKIP THE REST
+                let sent = self.kademlia.get_server().lock().unwrap().send_with_node_callback(&mut request, node, listener.clone());
+
+                if let Err(e) = sent {
+                    println!("{}", e);
+                }
+            }
+        }
+
+        Ok(())
     }
 
     fn clone_dyn(&self) -> Box<dyn Task> {
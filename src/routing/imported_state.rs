@@ -0,0 +1,196 @@
+use std::{fs, io};
+use std::net::SocketAddr;
+use std::path::Path;
+use rlibbencode::variables::bencode_array::BencodeArray;
+use rlibbencode::variables::bencode_bytes::BencodeBytes;
+use rlibbencode::variables::bencode_object::{BencodeObject, GetObject};
+use rlibbencode::variables::inter::bencode_variable::FromBencode;
+use crate::utils::net::address_types::{IPV4_LENGTH, IPV6_LENGTH};
+use crate::utils::net::address_utils::unpack_address;
+use crate::utils::uid::{ID_LENGTH, UID};
+
+pub const LIBTORRENT_DHT_STATE_KEY: &str = "dht state";
+pub const LIBTORRENT_NODE_ID_KEY: &str = "node-id";
+pub const TRANSMISSION_ID_KEY: &str = "id";
+pub const IMPORT_NODES_KEY: &str = "nodes";
+pub const IMPORT_NODES6_KEY: &str = "nodes6";
+
+//ROUTING STATE SAVED BY OTHER CLIENTS, THESE ONLY KEEP ENDPOINTS SO THE NODE IDS HAVE TO BE LEARNED BY PINGING THEM
+#[derive(Clone, Default, Debug)]
+pub struct ImportedState {
+    uid: Option<UID>,
+    addresses: Vec<SocketAddr>,
+    skipped: usize
+}
+
+impl ImportedState {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn load_libtorrent<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::from_libtorrent(&fs::read(path)?)
+    }
+
+    //ACCEPTS BOTH A FULL SESSION STATE WITH A "dht state" ENTRY AND A BARE DHT STATE
+    //node-id IS EITHER A SINGLE ID OR, SINCE LIBTORRENT 1.2, A LIST OF ID + INTERFACE ADDRESS
+    //nodes AND nodes6 ARE LISTS OF COMPACT ENDPOINTS, MALFORMED ENTRIES ARE SKIPPED AND COUNTED
+    pub fn from_libtorrent(buf: &[u8]) -> io::Result<Self> {
+        let ben = decode(buf)?;
+        let ben = ben.get::<BencodeObject>(LIBTORRENT_DHT_STATE_KEY).unwrap_or(&ben);
+        let mut state = Self::new();
+
+        if let Some(id) = ben.get::<BencodeBytes>(LIBTORRENT_NODE_ID_KEY) {
+            state.uid = Some(parse_uid(id.as_bytes())?);
+
+        } else if let Some(ids) = ben.get::<BencodeArray>(LIBTORRENT_NODE_ID_KEY) {
+            if let Some(id) = ids.get::<BencodeBytes>(0) {
+                state.uid = Some(parse_uid(id.as_bytes())?);
+            }
+        }
+
+        for key in [IMPORT_NODES_KEY, IMPORT_NODES6_KEY] {
+            if let Some(nodes) = ben.get::<BencodeArray>(key) {
+                for i in 0..nodes.len() {
+                    match nodes.get::<BencodeBytes>(i).map(|address| unpack_address(address.as_bytes())) {
+                        Some(Ok(address)) => state.addresses.push(address),
+                        _ => state.skipped += 1
+                    }
+                }
+            }
+        }
+
+        Ok(state)
+    }
+
+    pub fn load_transmission<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::from_transmission(&fs::read(path)?)
+    }
+
+    //TRANSMISSION dht.dat, nodes AND nodes6 ARE CONCATENATED COMPACT ENDPOINTS, A TRAILING PARTIAL ENDPOINT COUNTS AS SKIPPED
+    pub fn from_transmission(buf: &[u8]) -> io::Result<Self> {
+        let ben = decode(buf)?;
+        let mut state = Self::new();
+
+        if let Some(id) = ben.get::<BencodeBytes>(TRANSMISSION_ID_KEY) {
+            state.uid = Some(parse_uid(id.as_bytes())?);
+        }
+
+        for (key, length) in [(IMPORT_NODES_KEY, IPV4_LENGTH+2), (IMPORT_NODES6_KEY, IPV6_LENGTH+2)] {
+            if let Some(nodes) = ben.get::<BencodeBytes>(key) {
+                let chunks = nodes.as_bytes().chunks_exact(length);
+
+                if !chunks.remainder().is_empty() {
+                    state.skipped += 1;
+                }
+
+                for address in chunks {
+                    match unpack_address(address) {
+                        Ok(address) => state.addresses.push(address),
+                        Err(_) => state.skipped += 1
+                    }
+                }
+            }
+        }
+
+        Ok(state)
+    }
+
+    pub fn get_uid(&self) -> Option<UID> {
+        self.uid
+    }
+
+    pub fn get_addresses(&self) -> &Vec<SocketAddr> {
+        &self.addresses
+    }
+
+    pub fn get_skipped(&self) -> usize {
+        self.skipped
+    }
+}
+
+fn decode(buf: &[u8]) -> io::Result<BencodeObject> {
+    BencodeObject::from_bencode(buf).map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "State file is not valid bencode"))
+}
+
+//LIBTORRENT APPENDS THE INTERFACE ADDRESS TO THE ID, ONLY THE FIRST 20 BYTES ARE THE ID
+fn parse_uid(buf: &[u8]) -> io::Result<UID> {
+    if buf.len() < ID_LENGTH {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "State file id has the wrong length"));
+    }
+
+    let mut bid = [0u8; ID_LENGTH];
+    bid.copy_from_slice(&buf[..ID_LENGTH]);
+    Ok(UID::from(bid))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use rlibbencode::variables::bencode_array::AddArray;
+    use rlibbencode::variables::bencode_object::PutObject;
+    use rlibbencode::variables::inter::bencode_variable::ToBencode;
+    use crate::utils::net::address_utils::pack_address;
+    use super::*;
+
+    #[test]
+    fn import_formats() {
+        let v4: SocketAddr = "10.0.0.1:6881".parse().unwrap();
+        let v6: SocketAddr = "[2001:db8::1]:6882".parse().unwrap();
+
+        let mut ids = BencodeArray::new();
+        let mut id = [1u8; ID_LENGTH].to_vec();
+        id.extend_from_slice(&[10, 0, 0, 2]);
+        ids.push(id);
+
+        let mut nodes = BencodeArray::new();
+        nodes.push(pack_address(&v4));
+        let mut nodes6 = BencodeArray::new();
+        nodes6.push(pack_address(&v6));
+
+        let mut dht_state = BencodeObject::new();
+        dht_state.put(LIBTORRENT_NODE_ID_KEY, ids);
+        dht_state.put(IMPORT_NODES_KEY, nodes);
+        dht_state.put(IMPORT_NODES6_KEY, nodes6);
+        let mut session = BencodeObject::new();
+        session.put(LIBTORRENT_DHT_STATE_KEY, dht_state);
+
+        let state = ImportedState::from_libtorrent(&session.to_bencode()).unwrap();
+        assert!(state.get_uid() == Some(UID::from([1u8; ID_LENGTH])));
+        assert_eq!(state.get_addresses(), &vec![v4, v6]);
+
+        let mut dat = BencodeObject::new();
+        dat.put(TRANSMISSION_ID_KEY, [2u8; ID_LENGTH]);
+        dat.put(IMPORT_NODES_KEY, [pack_address(&v4), pack_address(&v4)].concat());
+        dat.put(IMPORT_NODES6_KEY, pack_address(&v6));
+
+        let state = ImportedState::from_transmission(&dat.to_bencode()).unwrap();
+        assert!(state.get_uid() == Some(UID::from([2u8; ID_LENGTH])));
+        assert_eq!(state.get_addresses(), &vec![v4, v4, v6]);
+
+        assert!(ImportedState::from_transmission(b"d2:id3:abce").is_err());
+    }
+
+    #[test]
+    fn import_corrupt_entries() {
+        let v4: SocketAddr = "10.0.0.1:6881".parse().unwrap();
+
+        let mut nodes = BencodeArray::new();
+        nodes.push(pack_address(&v4));
+        nodes.push(vec![10, 0, 0]);
+        let mut dht_state = BencodeObject::new();
+        dht_state.put(IMPORT_NODES_KEY, nodes);
+
+        let state = ImportedState::from_libtorrent(&dht_state.to_bencode()).unwrap();
+        assert_eq!(state.get_addresses(), &vec![v4]);
+        assert_eq!(state.get_skipped(), 1);
+
+        let mut dat = BencodeObject::new();
+        dat.put(IMPORT_NODES_KEY, [pack_address(&v4), vec![10, 0, 0]].concat());
+
+        let state = ImportedState::from_transmission(&dat.to_bencode()).unwrap();
+        assert_eq!(state.get_addresses(), &vec![v4]);
+        assert_eq!(state.get_skipped(), 1);
+    }
+}
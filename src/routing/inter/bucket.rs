@@ -0,0 +1,248 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::routing::kb::ls_comparator::ls_compare;
+use crate::utils::node::{Node, MAX_STALE_COUNT};
+
+//STALE, CACHE AND EVICTION HANDLING SHARED BY KBucket AND MBucket, THE BUCKETS ONLY HAND OUT THEIR STATE
+pub trait Bucket {
+
+    fn get_nodes(&self) -> &Vec<Node>;
+
+    fn get_nodes_mut(&mut self) -> &mut Vec<Node>;
+
+    fn get_cache(&self) -> &Vec<Node>;
+
+    fn get_cache_mut(&mut self) -> &mut Vec<Node>;
+
+    fn get_pending(&self) -> Option<(Node, Node)>;
+
+    fn set_pending(&mut self, pending: Option<(Node, Node)>);
+
+    fn get_k(&self) -> usize;
+
+    fn get_last_changed(&self) -> u128;
+
+    fn set_last_changed(&mut self, last_changed: u128);
+
+    //BEP 5, A BUCKET CHANGES WHEN A NODE IS ADDED OR REPLACED OR ONE OF ITS NODES ANSWERS US
+    fn touch(&mut self) {
+        self.set_last_changed(SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_millis());
+    }
+
+    fn is_full(&self) -> bool {
+        self.get_nodes().len() >= self.get_k()
+    }
+
+    //A NODE THAT IS SEEN AGAIN MOVES TO THE TAIL, WHICH ALSO ANSWERS A PENDING EVICTION CHECK
+    fn insert(&mut self, n: Node) {
+        if let Some(node) = self.get_nodes_mut().iter_mut().find(|c| n.eq(c)) {
+            if merge(node, &n) {
+                self.touch();
+            }
+
+            self.get_nodes_mut().sort_by(ls_compare);
+            self.cancel_eviction(&n);
+
+        } else if self.is_full() {
+            self.insert_cache(n);
+
+        } else {
+            self.get_nodes_mut().push(n);
+            self.get_nodes_mut().sort_by(ls_compare);
+            self.touch();
+        }
+    }
+
+    //THE CACHE IS KEPT IN THE ORDER ITS NODES WERE LAST SEEN, A NEW NODE ALWAYS GETS IN. WHEN FULL THE MOST STALE
+    //ENTRY MAKES ROOM, WITHOUT A STALE ONE THE LEAST RECENTLY SEEN ENTRY IS DROPPED
+    fn insert_cache(&mut self, n: Node) {
+        let k = self.get_k();
+        let cache = self.get_cache_mut();
+
+        if let Some(index) = cache.iter().position(|c| n.eq(c)) {
+            let mut node = cache.remove(index);
+            merge(&mut node, &n);
+            cache.push(node);
+            return;
+        }
+
+        if cache.len() >= k {
+            let index = cache.iter().enumerate()
+                .filter(|(_, c)| c.stale > 0)
+                .max_by_key(|(_, c)| c.stale)
+                .map(|(i, _)| i)
+                .unwrap_or(0);
+
+            cache.remove(index);
+        }
+
+        cache.push(n);
+    }
+
+    //STALE COUNTS LIVE ON THE BUCKETS COPY, A NODE THAT REACHES MAX_STALE_COUNT IS EVICTED AND THE
+    //FRESHEST VERIFIED CACHE ENTRY TAKES ITS PLACE
+    fn mark_stale(&mut self, n: &Node) {
+        if let Some(index) = self.get_nodes().iter().position(|c| n.eq(c)) {
+            self.get_nodes_mut()[index].mark_stale();
+
+            //A NODE THAT FAILS ITS EVICTION CHECK IS REPLACED BY THE CANDIDATE THAT TRIGGERED IT
+            let candidate = match self.get_pending() {
+                Some((pending, candidate)) if pending.eq(n) => {
+                    self.set_pending(None);
+                    self.get_cache_mut().retain(|c| !c.eq(&candidate));
+                    Some(candidate)
+                }
+                _ => None
+            };
+
+            if candidate.is_some() || self.get_nodes()[index].stale >= MAX_STALE_COUNT {
+                self.get_nodes_mut().remove(index);
+
+                if let Some(node) = candidate.or_else(|| self.take_replacement()) {
+                    self.get_nodes_mut().push(node);
+                    self.get_nodes_mut().sort_by(ls_compare);
+                }
+
+                self.touch();
+            }
+
+        } else if let Some(node) = self.get_cache_mut().iter_mut().find(|c| n.eq(c)) {
+            node.mark_stale();
+        }
+    }
+
+    //VERIFIED MEANS IT HAS ANSWERED US WITHOUT FAILING A QUERY SINCE
+    fn take_replacement(&mut self) -> Option<Node> {
+        let index = self.get_cache().iter().enumerate()
+            .filter(|(_, c)| c.stale == 0 && c.last_response > 0)
+            .max_by_key(|(_, c)| c.last_response)
+            .map(|(i, _)| i)?;
+
+        Some(self.get_cache_mut().remove(index))
+    }
+
+    //THE LEAST RECENTLY SEEN NODE IS CHECKED ONE CANDIDATE AT A TIME, LATER CANDIDATES WAIT IN THE CACHE
+    fn check_eviction(&mut self, candidate: Node) -> Option<Node> {
+        if self.get_pending().is_some() || !self.is_full() || self.get_nodes().contains(&candidate) {
+            return None;
+        }
+
+        let oldest = *self.get_nodes().first()?;
+        self.set_pending(Some((oldest, candidate)));
+        Some(oldest)
+    }
+
+    fn cancel_eviction(&mut self, n: &Node) {
+        if self.get_pending().is_some_and(|(pending, _)| pending.eq(n)) {
+            self.set_pending(None);
+        }
+    }
+
+    fn contains_ip(&self, n: &Node) -> bool {
+        self.get_nodes().contains(n) || self.get_cache().contains(n)
+    }
+
+    fn contains_uid(&self, n: &Node) -> bool {
+        self.get_nodes().iter().any(|c| c.verify(n)) || self.get_cache().iter().any(|c| c.verify(n))
+    }
+
+    fn has_queried(&self, n: &Node, now: u128, query_time: u128) -> bool {
+        self.get_nodes().iter().find(|&c| c.eq(n)).is_some_and(|c| c.has_queried(now, query_time))
+    }
+
+    fn unqueried_nodes(&self, now: u128, query_time: u128) -> Vec<Node> {
+        self.get_nodes().iter().filter(|&n| !n.has_queried(now, query_time)).cloned().collect()
+    }
+}
+
+//A NEWER RESPONSE ON THE INCOMING COPY CLEARS THE FAILED QUERIES, ANYTHING ELSE ONLY COUNTS AS ACTIVITY
+fn merge(node: &mut Node, n: &Node) -> bool {
+    if n.last_response > node.last_response {
+        node.responded();
+        return true;
+    }
+
+    node.seen();
+    false
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+
+    use std::net::SocketAddr;
+    use crate::utils::uid::{ID_LENGTH, UID};
+    use super::*;
+
+    //SHARED BY THE KBucket AND MBucket TESTS, THE BUCKETS ARE EXPECTED EMPTY WITH A k OF 2
+    fn nodes(responded: bool) -> Vec<Node> {
+        (1..=5u8).map(|i| {
+            let mut node = Node::new(UID::from([i; ID_LENGTH]), SocketAddr::from(([10, 0, 0, i], 6881)));
+            if responded {
+                node.responded();
+            }
+            node
+        }).collect()
+    }
+
+    pub fn stale_promotion(bucket: &mut dyn Bucket) {
+        let nodes = nodes(true);
+        nodes.iter().take(4).for_each(|n| bucket.insert(*n));
+        assert_eq!(bucket.get_cache().len(), 2);
+
+        //THE CACHE ENTRY THAT FAILED ISNT VERIFIED, SO THE OTHER ONE IS PROMOTED
+        bucket.mark_stale(&nodes[3]);
+
+        for _ in 0..MAX_STALE_COUNT-1 {
+            bucket.mark_stale(&nodes[0]);
+        }
+        assert!(bucket.get_nodes().contains(&nodes[0]));
+
+        bucket.mark_stale(&nodes[0]);
+        assert!(!bucket.get_nodes().contains(&nodes[0]));
+        assert!(bucket.get_nodes().contains(&nodes[2]));
+        assert!(*bucket.get_cache() == vec![nodes[3]]);
+    }
+
+    pub fn cache_policy(bucket: &mut dyn Bucket) {
+        let nodes = nodes(false);
+        nodes.iter().take(4).for_each(|n| bucket.insert(*n));
+
+        //A STALE ENTRY MAKES ROOM FIRST
+        bucket.mark_stale(&nodes[3]);
+        bucket.insert(nodes[4]);
+        assert!(*bucket.get_cache() == vec![nodes[2], nodes[4]]);
+
+        //WITHOUT ONE THE LEAST RECENTLY SEEN ENTRY IS DROPPED, SEEING AN ENTRY AGAIN MOVES IT TO THE TAIL
+        bucket.insert(nodes[2]);
+        bucket.insert(nodes[3]);
+        assert!(*bucket.get_cache() == vec![nodes[2], nodes[3]]);
+    }
+
+    pub fn eviction_check(bucket: &mut dyn Bucket) {
+        let nodes = nodes(false);
+        nodes.iter().take(3).for_each(|n| bucket.insert(*n));
+
+        let oldest = bucket.check_eviction(nodes[2]).unwrap();
+        assert!(oldest == bucket.get_nodes()[0]);
+        bucket.insert(nodes[3]);
+        assert!(bucket.check_eviction(nodes[3]).is_none());
+
+        //AN ANSWER MOVES IT TO THE TAIL AND ENDS THE CHECK
+        bucket.insert(oldest);
+        assert!(*bucket.get_nodes().last().unwrap() == oldest);
+        assert!(bucket.get_pending().is_none());
+
+        //A CANCELLED CHECK LEAVES THE NODE IN PLACE
+        let oldest = bucket.check_eviction(nodes[3]).unwrap();
+        bucket.cancel_eviction(&oldest);
+        assert!(bucket.get_pending().is_none() && bucket.get_nodes().contains(&oldest));
+
+        //NO ANSWER, THE CANDIDATE TAKES ITS PLACE
+        let oldest = bucket.check_eviction(nodes[3]).unwrap();
+        bucket.mark_stale(&oldest);
+        assert!(!bucket.get_nodes().contains(&oldest));
+        assert!(bucket.get_nodes().contains(&nodes[3]) && !bucket.get_cache().contains(&nodes[3]));
+    }
+}
@@ -1 +1,2 @@
 pub mod routing_table;
+pub mod bucket;
\ No newline at end of file
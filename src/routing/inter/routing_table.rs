@@ -1,8 +1,13 @@
 use std::any::Any;
+use std::io;
 use std::net::IpAddr;
 use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::routing::kb::k_comparator::KComparator;
+use crate::utils;
 use crate::utils::node::Node;
-use crate::utils::uid::UID;
+use crate::utils::node_state::NodeState;
+use crate::utils::uid::{ID_LENGTH, UID};
 
 pub trait RoutingTable: Send {
 
@@ -14,20 +19,54 @@ pub trait RoutingTable: Send {
 
     fn get_consensus_external_address(&self) -> IpAddr;
 
-    fn insert(&mut self, n: Node);
+    //RETURNS THE LEAST RECENTLY SEEN NODE OF A FULL BUCKET THAT HAS TO BE PINGED BEFORE n CAN REPLACE IT,
+    //USE insert_checked SO THE EVICTION LISTENERS GET IT
+    fn insert(&mut self, n: Node) -> Option<Node>;
+
+    //INSERTS n WITHOUT STARTING AN EVICTION CHECK, FOR REBUILDING THE TABLE FROM NODES IT ALREADY HAD
+    fn insert_unchecked(&mut self, n: Node);
+
+    //EMPTIES THE BUCKETS AND LAYS THEM OUT AROUND uid
+    fn reset_buckets(&mut self, uid: UID);
+
+    fn mark_stale(&mut self, n: &Node);
 
     fn derive_uid(&mut self);
 
     fn get_derived_uid(&self) -> UID;
 
+    fn set_derived_uid(&mut self, uid: UID);
+
+    fn set_uid_seed(&mut self, seed: [u8; ID_LENGTH]);
+
+    fn get_uid_seed(&self) -> Option<[u8; ID_LENGTH]>;
+
+    fn set_explicit_uid(&mut self, uid: UID) -> io::Result<()>;
+
+    fn get_explicit_uid(&self) -> Option<UID>;
+
     fn is_secure_only(&self) -> bool;
 
     fn set_secure_only(&mut self, secure_only: bool);
 
+    fn set_k(&mut self, k: usize);
+
+    fn get_k(&self) -> usize;
+
+    fn set_query_time(&mut self, query_time: u128);
+
+    fn get_query_time(&self) -> u128;
+
     fn add_restart_listener(&mut self, listener: RestartListener);
 
     fn remove_restart_listener(&mut self, index: usize);
 
+    fn add_eviction_listener(&mut self, listener: EvictionListener);
+
+    fn remove_eviction_listener(&mut self, index: usize);
+
+    fn get_eviction_listeners(&self) -> Vec<EvictionListener>;
+
     fn has_queried(&self, n: &Node, now: u128) -> bool;
 
     fn bucket_uid(&self, k: &UID) -> usize;
@@ -38,6 +77,8 @@ pub trait RoutingTable: Send {
 
     fn bucket_size(&self, i: usize) -> usize;
 
+    fn take_idle_bucket(&mut self, idle_time: u128) -> Option<UID>;
+
     fn all_unqueried_nodes(&self) -> Vec<Node>;
 
     fn get_restart(&self) -> fn(Arc<Mutex<dyn RoutingTable>>);
@@ -52,3 +93,179 @@ pub trait RoutingTable: Send {
 }
 
 pub type RestartListener = Arc<dyn Fn() + Send + Sync>;
+
+//CALLED BY insert_checked ONCE THE ROUTING TABLE IS UNLOCKED
+pub type EvictionListener = Arc<dyn Fn(Node) + Send + Sync>;
+
+//INSERTS n AND HANDS A PENDING EVICTION CHECK TO THE LISTENERS AFTER THE LOCK IS RELEASED, SENDING THE PING LOCKS THE TABLE AGAIN
+pub fn insert_checked(routing_table: &Arc<Mutex<dyn RoutingTable>>, n: Node) {
+    let (oldest, listeners) = {
+        let mut routing_table = routing_table.lock().unwrap();
+
+        match routing_table.insert(n) {
+            Some(oldest) => (oldest, routing_table.get_eviction_listeners()),
+            None => return
+        }
+    };
+
+    for listener in listeners {
+        listener(oldest);
+    }
+}
+
+//AN EXPLICIT ID ONLY HOLDS WHILE secure_only IS OFF, OTHERWISE THE ID IS DERIVED FROM THE
+//CONSENSUS ADDRESS WITH THE SEED, OR FRESH RANDOM BITS WITHOUT ONE
+pub fn derive_uid(explicit_uid: Option<UID>, secure_only: bool, address: IpAddr, uid_seed: Option<[u8; ID_LENGTH]>) -> UID {
+    match explicit_uid {
+        Some(uid) if !secure_only => uid,
+        _ => UID::derive_secure(address, &uid_seed.unwrap_or_else(utils::random::gen_array::<u8, ID_LENGTH>))
+    }
+}
+
+//THE BUCKET LAYOUT DEPENDS ON OUR ID, SO EXISTING NODES ARE RE-INSERTED
+pub fn set_derived_uid(routing_table: &mut dyn RoutingTable, uid: UID) {
+    let nodes = routing_table.all_nodes();
+    routing_table.reset_buckets(uid);
+
+    for node in nodes {
+        routing_table.insert_unchecked(node);
+    }
+}
+
+//BAD NODES ARE NEVER HANDED OUT, QUESTIONABLE ONES ONLY FILL WHAT THE GOOD ONES LEAVE OPEN
+pub fn find_closest(nodes: Vec<Node>, k: &UID, r: usize, query_time: u128) -> Vec<Node> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_millis();
+
+    let mut nodes: Vec<(NodeState, Node)> = nodes.into_iter()
+        .map(|n| (n.get_state(now, query_time), n))
+        .filter(|(state, _)| *state != NodeState::Bad)
+        .collect();
+
+    let comparator = KComparator::new(k);
+    nodes.sort_by(|(a_state, a), (b_state, b)| (*a_state != NodeState::Good).cmp(&(*b_state != NodeState::Good))
+        .then_with(|| comparator.compare(a, b)));
+
+    let mut nodes: Vec<Node> = nodes.into_iter().take(r).map(|(_, n)| n).collect();
+    nodes.sort_by(|a, b| comparator.compare(a, b));
+    nodes
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+
+    use std::net::SocketAddr;
+    use crate::refresh::tasks::bucket_refresh_task::BUCKET_REFRESH_TIME;
+    use crate::utils::node::{MAX_STALE_COUNT, QUERY_TIME};
+    use super::*;
+
+    //SHARED BY THE KRoutingTable AND MRoutingTable TESTS, EACH TAKES A CONSTRUCTOR FOR THE TABLE UNDER TEST
+    pub type NewRoutingTable = fn() -> Arc<Mutex<dyn RoutingTable>>;
+
+    //NODES AT THE FARTHEST DISTANCE FROM uid, ALL IN THE SAME BUCKET
+    fn far_nodes(uid: UID, count: u8) -> Vec<Node> {
+        (1..=count).map(|i| {
+            let mut bid = uid.bytes();
+            bid[0] ^= 0x80;
+            bid[ID_LENGTH-1] ^= i;
+            Node::new(UID::from(bid), SocketAddr::from(([10, 0, 0, i], 6881)))
+        }).collect()
+    }
+
+    pub fn eviction_listener(new: NewRoutingTable) {
+        let routing_table = new();
+        routing_table.lock().unwrap().set_secure_only(false);
+        routing_table.lock().unwrap().set_k(1);
+        let uid = routing_table.lock().unwrap().get_derived_uid();
+        let nodes = far_nodes(uid, 4);
+
+        //WITHOUT A LISTENER NOTHING WOULD PING THE NODE, SO NO CHECK IS STARTED
+        assert!(routing_table.lock().unwrap().insert(nodes[0]).is_none());
+        assert!(routing_table.lock().unwrap().insert(nodes[1]).is_none());
+
+        //THE LISTENER LOCKS THE TABLE LIKE A PING BEING SENT, SO IT MUST BE CALLED WITHOUT THE LOCK HELD
+        let evicted = Arc::new(Mutex::new(Vec::new()));
+        routing_table.lock().unwrap().add_eviction_listener(Arc::new({
+            let routing_table = Arc::downgrade(&routing_table);
+            let evicted = evicted.clone();
+            move |node| {
+                let routing_table = routing_table.upgrade().unwrap();
+                let mut routing_table = routing_table.try_lock().expect("Routing table is still locked");
+                routing_table.mark_stale(&node);
+                evicted.lock().unwrap().push(node);
+            }
+        }));
+
+        //EVERY CHECK FAILED, SO EACH CANDIDATE TOOK THE PLACE OF THE NODE IT CHECKED
+        nodes.iter().skip(2).for_each(|n| insert_checked(&routing_table, *n));
+        assert!(*evicted.lock().unwrap() == vec![nodes[0], nodes[2]]);
+        assert!(routing_table.lock().unwrap().all_nodes() == vec![nodes[3]]);
+    }
+
+    pub fn node_states(new: NewRoutingTable) {
+        let routing_table = new();
+        let mut routing_table = routing_table.lock().unwrap();
+        routing_table.set_secure_only(false);
+        let uid = routing_table.get_derived_uid();
+
+        //ONLY QUERIED US, ANSWERED, ANSWERED BUT FAILED EVERY QUERY SINCE
+        let mut nodes = Vec::new();
+        for i in 1..=3u8 {
+            let mut bid = uid.bytes();
+            bid[ID_LENGTH-1] ^= i;
+            nodes.push(Node::new(UID::from(bid), SocketAddr::from(([10, 0, 0, i], 6881))));
+        }
+
+        nodes[1].responded();
+        nodes[2].responded();
+        for _ in 0..MAX_STALE_COUNT {
+            nodes[2].mark_stale();
+        }
+        nodes.iter().for_each(|n| { routing_table.insert(*n); });
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+        let states: Vec<NodeState> = routing_table.all_nodes().iter().map(|n| n.get_state(now, QUERY_TIME)).collect();
+        assert!(states.contains(&NodeState::Questionable) && states.contains(&NodeState::Good) && states.contains(&NodeState::Bad));
+        assert_eq!(nodes[1].get_state(now+QUERY_TIME, QUERY_TIME), NodeState::Questionable);
+
+        //THE GOOD NODE COMES FIRST EVEN THOUGH THE QUESTIONABLE ONE IS CLOSER, THE BAD ONE NEVER
+        let closest = routing_table.find_closest(&uid, 1);
+        assert!(closest.len() == 1 && closest[0] == nodes[1]);
+        assert!(routing_table.find_closest(&uid, 3) == vec![nodes[0], nodes[1]]);
+    }
+
+    pub fn idle_buckets(new: NewRoutingTable) {
+        let routing_table = new();
+        let mut routing_table = routing_table.lock().unwrap();
+        routing_table.set_secure_only(false);
+        let uid = routing_table.get_derived_uid();
+
+        //THE FARTHEST IDLE BUCKET IS HANDED OUT FIRST, EVERY BUCKET ONLY ONCE PER idle_time
+        let mut distances = Vec::new();
+        while let Some(target) = routing_table.take_idle_bucket(BUCKET_REFRESH_TIME) {
+            distances.push(uid.distance(&target));
+        }
+
+        assert_eq!(distances.first(), Some(&(ID_LENGTH*8)));
+        let len = distances.len();
+        distances.sort();
+        distances.dedup();
+        assert_eq!(distances.len(), len);
+
+        //A TABLE WITH NO IDLE TIME HANDS OUT THE BUCKET AGAIN
+        assert_eq!(uid.distance(&routing_table.take_idle_bucket(0).unwrap()), ID_LENGTH*8);
+
+        //A NEW NODE COUNTS AS A CHANGE, SO THE BUCKET HOLDING IT ISNT IDLE
+        let routing_table = new();
+        let mut routing_table = routing_table.lock().unwrap();
+        routing_table.set_secure_only(false);
+        let uid = routing_table.get_derived_uid();
+        routing_table.insert(far_nodes(uid, 1)[0]);
+
+        while let Some(target) = routing_table.take_idle_bucket(BUCKET_REFRESH_TIME) {
+            assert!(uid.distance(&target) < ID_LENGTH*8);
+        }
+    }
+}
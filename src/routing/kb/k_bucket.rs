@@ -1,92 +1,64 @@
-use crate::routing::kb::ls_comparator::ls_compare;
+use crate::routing::inter::bucket::Bucket;
 use crate::utils::node::Node;
 
-pub const MAX_BUCKET_SIZE: usize = 5; //SHOULD PROBABLY CHANGE THIS IN SOME WAY... - USAGES ARE HIGH - WONT ALLOW FOR M_BUCKET TO UTILIZE THIS
-const MAX_STALE_COUNT: u32 = 1;
+pub const MAX_BUCKET_SIZE: usize = 5; //DEFAULT K, Config OVERRIDES IT PER ROUTING TABLE
 
 pub struct KBucket {
     pub(crate) nodes: Vec<Node>,
-    pub(crate) cache: Vec<Node>
+    pub(crate) cache: Vec<Node>,
+    pub(crate) pending: Option<(Node, Node)>,
+    pub(crate) last_changed: u128,
+    pub(crate) k: usize
 }
 
 impl KBucket {
 
-    pub fn new() -> Self {
+    pub fn new(k: usize) -> Self {
         Self {
             nodes: Vec::new(),
-            cache: Vec::new()
+            cache: Vec::new(),
+            pending: None,
+            last_changed: 0,
+            k
         }
     }
+}
 
-    pub fn insert(&mut self, n: Node) {
-        if let Some(node) = self.nodes.iter_mut().find(|c| n.eq(c)) {
-            node.seen();
-            self.nodes.sort_by(|a, b| ls_compare(a, b));
-
-        } else if self.nodes.len() >= MAX_BUCKET_SIZE {
-            if let Some(node) = self.cache.iter_mut().find(|c| n.eq(c)) {
-                node.seen();
-
-            } else if self.cache.len() >= MAX_BUCKET_SIZE {
-                let mut index = MAX_BUCKET_SIZE+1;
-
-                for i in 0..self.cache.len() {
-                    if self.cache.get(i).unwrap().stale >= MAX_STALE_COUNT {
-                        if index < MAX_BUCKET_SIZE && self.cache.get(i).unwrap().stale > self.cache.get(index).unwrap().stale {
-                            index = i;
-                        }
-                    }
-                }
-
-                if index < MAX_BUCKET_SIZE {
-                    self.cache.remove(index);
-                    self.cache.push(n);
-                }
+impl Bucket for KBucket {
 
-            }else{
-                self.cache.push(n);
-            }
-        }else{
-            self.nodes.push(n);
-            self.nodes.sort_by(|a, b| ls_compare(a, b));
-        }
+    fn get_nodes(&self) -> &Vec<Node> {
+        &self.nodes
     }
 
-    pub fn contains_ip(&self, n: &Node) -> bool {
-        self.nodes.contains(&n) || self.cache.contains(&n)
+    fn get_nodes_mut(&mut self) -> &mut Vec<Node> {
+        &mut self.nodes
     }
 
-    pub fn contains_uid(&self, n: &Node) -> bool {
-        self.nodes.iter().any(|c| c.verify(&n)) || self.cache.iter().any(|c| c.verify(&n))
+    fn get_cache(&self) -> &Vec<Node> {
+        &self.cache
     }
 
-    pub fn has_queried(&self, n: &Node, now: u128) -> bool {
-        for c in &self.nodes {
-            if c.eq(&n) {
-                return c.has_queried(now);
-            }
-        }
+    fn get_cache_mut(&mut self) -> &mut Vec<Node> {
+        &mut self.cache
+    }
 
-        false
+    fn get_pending(&self) -> Option<(Node, Node)> {
+        self.pending
     }
 
-    /*
-    fn all_nodes(&self) -> Vec<Node> {
-        self.nodes.clone()
+    fn set_pending(&mut self, pending: Option<(Node, Node)>) {
+        self.pending = pending;
     }
-    */
 
-    pub fn unqueried_nodes(&self, now: u128) -> Vec<Node> {
-        self.nodes.iter().filter(|&n| !n.has_queried(now)).cloned().collect()
+    fn get_k(&self) -> usize {
+        self.k
     }
 
-    /*
-    fn size(&self) -> usize {
-        self.nodes.len()
+    fn get_last_changed(&self) -> u128 {
+        self.last_changed
     }
 
-    fn csize(&self) -> usize {
-        self.cache.len()
+    fn set_last_changed(&mut self, last_changed: u128) {
+        self.last_changed = last_changed;
     }
-    */
 }
@@ -1,24 +1,28 @@
+use std::io;
 use std::net::IpAddr;
 use std::time::{SystemTime, UNIX_EPOCH};
 use core::array::from_fn;
 use std::any::Any;
 use std::sync::{Arc, Mutex};
-use crate::routing::inter::routing_table::{RestartListener, RoutingTable};
-use crate::utils;
-use crate::utils::hash::crc32c::Crc32c;
+use crate::routing::inter::bucket::Bucket;
+use crate::routing::inter::routing_table::{derive_uid, find_closest, set_derived_uid, EvictionListener, RestartListener, RoutingTable};
 use crate::utils::linked_hashmap::LinkedHashMap;
 use crate::utils::net::address_utils::is_global_unicast;
-use super::k_bucket::KBucket;
-use super::k_comparator::KComparator;
-use crate::utils::node::{Node, V4_MASK, V6_MASK};
+use super::k_bucket::{KBucket, MAX_BUCKET_SIZE};
+use crate::utils::node::{Node, QUERY_TIME};
 use crate::utils::uid::{ UID, ID_LENGTH };
 
 pub struct KRoutingTable {
     uid: Option<UID>,
+    uid_seed: Option<[u8; ID_LENGTH]>,
+    explicit_uid: Option<UID>,
     listeners: Vec<RestartListener>,
+    eviction_listeners: Vec<EvictionListener>,
     consensus_external_address: IpAddr,
     origin_pairs: LinkedHashMap<IpAddr, IpAddr>,
     secure_only: bool,
+    k: usize,
+    query_time: u128,
     k_buckets: [KBucket; ID_LENGTH*8]
 }
 
@@ -27,16 +31,56 @@ impl KRoutingTable {
     pub fn new() -> Self {
         let mut routing_table = Self {
             uid: None,
+            uid_seed: None,
+            explicit_uid: None,
             listeners: Vec::new(),
+            eviction_listeners: Vec::new(),
             consensus_external_address: IpAddr::from([127, 0, 1, 1]),
             origin_pairs: LinkedHashMap::with_capacity(64),
             secure_only: true,
-            k_buckets: from_fn(|_| KBucket::new())
+            k: MAX_BUCKET_SIZE,
+            query_time: QUERY_TIME,
+            k_buckets: from_fn(|_| KBucket::new(MAX_BUCKET_SIZE))
         };
 
         routing_table.derive_uid();
         routing_table
     }
+
+    //REBUILDING THE TABLE SKIPS THE EVICTION CHECKS, THOSE ONLY MAKE SENSE FOR NODES WE JUST HEARD FROM
+    fn insert_node(&mut self, n: Node, check_eviction: bool) -> Option<Node> {
+        if self.secure_only && !n.has_secure_id() {
+            return None;
+        }
+
+        if let Some(uid) = &self.uid {
+            if *uid != n.uid {
+                let id = self.bucket_uid(&n.uid);
+
+                let mut contains_ip = false;
+                for b in &self.k_buckets {
+                    if b.contains_ip(&n) {
+                        contains_ip = true;
+                        break;
+                    }
+                }
+
+                let contains_uid = self.k_buckets[id].contains_uid(&n);
+
+                if contains_ip == contains_uid {
+                    self.k_buckets[id].insert(n);
+
+                    //A FULL BUCKET PINGS ITS LEAST RECENTLY SEEN NODE BEFORE THE CANDIDATE CAN REPLACE IT, WITHOUT
+                    //A LISTENER NOTHING WOULD SEND THE PING SO NO CHECK IS STARTED
+                    if check_eviction && !self.eviction_listeners.is_empty() {
+                        return self.k_buckets[id].check_eviction(n);
+                    }
+                }
+            }
+        }
+
+        None
+    }
 }
 
 impl RoutingTable for KRoutingTable {
@@ -82,74 +126,62 @@ impl RoutingTable for KRoutingTable {
         self.consensus_external_address
     }
 
-    fn insert(&mut self, n: Node) {
-        if self.secure_only && !n.has_secure_id() {
-            return
-        }
+    fn insert(&mut self, n: Node) -> Option<Node> {
+        self.insert_node(n, true)
+    }
+
+    fn insert_unchecked(&mut self, n: Node) {
+        self.insert_node(n, false);
+    }
+
+    fn reset_buckets(&mut self, uid: UID) {
+        self.uid = Some(uid);
+        self.k_buckets = from_fn(|_| KBucket::new(self.k));
+    }
 
+    fn mark_stale(&mut self, n: &Node) {
         if let Some(uid) = &self.uid {
             if *uid != n.uid {
                 let id = self.bucket_uid(&n.uid);
-
-                let mut contains_ip = false;
-                for b in &self.k_buckets {
-                    if b.contains_ip(&n) {
-                        contains_ip = true;
-                        break;
-                    }
-                }
-
-                let contains_uid = self.k_buckets[id].contains_uid(&n);
-
-                if contains_ip == contains_uid {
-                    self.k_buckets[id].insert(n);
-                }
+                self.k_buckets[id].mark_stale(n);
             }
         }
     }
 
     fn derive_uid(&mut self) {
-        let mut ip: Vec<u8> = match self.consensus_external_address {
-            IpAddr::V4(v4) => v4.octets().to_vec(),
-            IpAddr::V6(v6) => v6.octets().to_vec(),
-        };
-
-        let mask: Vec<u8> = if ip.len() == 4 {
-            V4_MASK.to_vec()
-        } else {
-            V6_MASK.to_vec()
-        };
-
-        for i in 0..mask.len() {
-            ip[i] &= mask[i];
-        }
+        self.uid = Some(derive_uid(self.explicit_uid, self.secure_only, self.consensus_external_address, self.uid_seed));
+    }
 
-        //let mut rng = rand::thread_rng();
-        let rand: u8 = /*rng.gen::<u8>()*/utils::random::gen::<u8>() & 0xFF;
-        let r = rand & 0x7;
+    fn get_derived_uid(&self) -> UID {
+        self.uid.unwrap()
+    }
 
-        ip[0] |= r << 5;
+    fn set_derived_uid(&mut self, uid: UID) {
+        set_derived_uid(self, uid);
+    }
 
-        let mut c = Crc32c::new();
-        c.update(&ip, 0, ip.len());
-        let crc = c.get_value();
+    fn set_uid_seed(&mut self, seed: [u8; ID_LENGTH]) {
+        self.uid_seed = Some(seed);
+        self.derive_uid();
+        self.set_derived_uid(self.get_derived_uid());
+    }
 
-        let mut bid = [0u8; ID_LENGTH];
-        bid[0] = (crc >> 24) as u8;
-        bid[1] = (crc >> 16) as u8;
-        bid[2] = ((crc >> 8) as u8 & 0xF8) | (/*rng.gen::<u8>()*/utils::random::gen::<u8>() & 0x7);
+    fn get_uid_seed(&self) -> Option<[u8; ID_LENGTH]> {
+        self.uid_seed
+    }
 
-        for i in 3..19 {
-            bid[i] = /*rng.gen::<u8>()*/utils::random::gen::<u8>() & 0xFF;
+    fn set_explicit_uid(&mut self, uid: UID) -> io::Result<()> {
+        if self.secure_only {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "An explicit ID requires secure_only to be off"));
         }
 
-        bid[19] = rand & 0xFF;
-
-        self.uid = Some(UID::from(bid));
+        self.explicit_uid = Some(uid);
+        self.set_derived_uid(uid);
+        Ok(())
     }
 
-    fn get_derived_uid(&self) -> UID {
-        self.uid.unwrap()
+    fn get_explicit_uid(&self) -> Option<UID> {
+        self.explicit_uid
     }
 
     fn is_secure_only(&self) -> bool {
@@ -160,6 +192,26 @@ impl RoutingTable for KRoutingTable {
         self.secure_only = secure_only;
     }
 
+    fn set_k(&mut self, k: usize) {
+        self.k = k.max(1);
+
+        for b in self.k_buckets.iter_mut() {
+            b.k = self.k;
+        }
+    }
+
+    fn get_k(&self) -> usize {
+        self.k
+    }
+
+    fn set_query_time(&mut self, query_time: u128) {
+        self.query_time = query_time;
+    }
+
+    fn get_query_time(&self) -> u128 {
+        self.query_time
+    }
+
     fn add_restart_listener(&mut self, listener: RestartListener) {
         self.listeners.push(listener);
     }
@@ -168,6 +220,18 @@ impl RoutingTable for KRoutingTable {
         let _ = self.listeners.remove(index);
     }
 
+    fn add_eviction_listener(&mut self, listener: EvictionListener) {
+        self.eviction_listeners.push(listener);
+    }
+
+    fn remove_eviction_listener(&mut self, index: usize) {
+        let _ = self.eviction_listeners.remove(index);
+    }
+
+    fn get_eviction_listeners(&self) -> Vec<EvictionListener> {
+        self.eviction_listeners.clone()
+    }
+
     fn has_queried(&self, n: &Node, now: u128) -> bool {
         let id = self.bucket_uid(&n.uid);
 
@@ -175,7 +239,7 @@ impl RoutingTable for KRoutingTable {
             return false;
         }
 
-        self.k_buckets[id].has_queried(n, now)
+        self.k_buckets[id].has_queried(n, now, self.query_time)
     }
 
     fn bucket_uid(&self, k: &UID) -> usize {
@@ -193,21 +257,26 @@ impl RoutingTable for KRoutingTable {
     }
 
     fn find_closest(&self, k: &UID, r: usize) -> Vec<Node> {
-        let mut nodes = self.all_nodes();
-        let comparator = KComparator::new(k);
-        nodes.sort_by(|a, b| comparator.compare(a, b));
-
-        if nodes.len() > r {
-            return nodes[0..r].to_vec();
-        }
-
-        nodes
+        find_closest(self.all_nodes(), k, r, self.query_time)
     }
 
     fn bucket_size(&self, i: usize) -> usize {
         self.k_buckets[i].nodes.len()
     }
 
+    //MARKS THE FARTHEST BUCKET THAT HASNT CHANGED WITHIN idle_time AS REFRESHED AND RETURNS A RANDOM ID IN ITS RANGE
+    fn take_idle_bucket(&mut self, idle_time: u128) -> Option<UID> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_millis();
+
+        let uid = self.uid?;
+        let (i, bucket) = self.k_buckets.iter_mut().enumerate().rev().find(|(_, b)| now.saturating_sub(b.get_last_changed()) >= idle_time)?;
+        bucket.touch();
+        Some(uid.generate_random_node_id_by_distance(i+1))
+    }
+
     fn all_unqueried_nodes(&self) -> Vec<Node> {
         let mut nodes = Vec::new();
 
@@ -217,7 +286,7 @@ impl RoutingTable for KRoutingTable {
             .as_millis();
 
         for b in &self.k_buckets {
-            nodes.extend(&b.unqueried_nodes(now));
+            nodes.extend(&b.unqueried_nodes(now, self.query_time));
         }
 
         nodes
@@ -231,10 +300,11 @@ impl RoutingTable for KRoutingTable {
         routing_table.lock().unwrap().as_any_mut().downcast_mut::<Self>().unwrap().derive_uid();
 
         let nodes = routing_table.lock().unwrap().all_nodes();
-        routing_table.lock().unwrap().as_any_mut().downcast_mut::<Self>().unwrap().k_buckets = from_fn(|_| KBucket::new());
+        let k = routing_table.lock().unwrap().get_k();
+        routing_table.lock().unwrap().as_any_mut().downcast_mut::<Self>().unwrap().k_buckets = from_fn(|_| KBucket::new(k));
 
         for node in nodes {
-            routing_table.lock().unwrap().as_any_mut().downcast_mut::<Self>().unwrap().insert(node);
+            routing_table.lock().unwrap().as_any_mut().downcast_mut::<Self>().unwrap().insert_node(node, false);
         }
 
         if routing_table.lock().unwrap().as_any().downcast_ref::<Self>().unwrap().listeners.is_empty() {
@@ -259,3 +329,41 @@ impl RoutingTable for KRoutingTable {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use crate::routing::inter::{bucket, routing_table};
+    use super::*;
+
+    #[test]
+    fn stale_promotion() {
+        bucket::tests::stale_promotion(&mut KBucket::new(2));
+    }
+
+    #[test]
+    fn cache_policy() {
+        bucket::tests::cache_policy(&mut KBucket::new(2));
+    }
+
+    #[test]
+    fn eviction_check() {
+        bucket::tests::eviction_check(&mut KBucket::new(2));
+    }
+
+    #[test]
+    fn eviction_listener() {
+        routing_table::tests::eviction_listener(|| Arc::new(Mutex::new(KRoutingTable::new())));
+    }
+
+    #[test]
+    fn node_states() {
+        routing_table::tests::node_states(|| Arc::new(Mutex::new(KRoutingTable::new())));
+    }
+
+    #[test]
+    fn idle_buckets() {
+        routing_table::tests::idle_buckets(|| Arc::new(Mutex::new(KRoutingTable::new())));
+    }
+
+}
@@ -1,23 +1,64 @@
+use crate::routing::inter::bucket::Bucket;
 use crate::utils::node::Node;
 
-pub const MAX_BUCKET_SIZE: usize = 5; //SHOULD PROBABLY CHANGE THIS IN SOME WAY...
-const MAX_STALE_COUNT: u32 = 1;
+pub const MAX_BUCKET_SIZE: usize = 5; //DEFAULT K, Config OVERRIDES IT PER ROUTING TABLE
 
 pub struct MBucket {
     pub(crate) nodes: Vec<Node>,
-    pub(crate) cache: Vec<Node>
+    pub(crate) cache: Vec<Node>,
+    pub(crate) pending: Option<(Node, Node)>,
+    pub(crate) last_changed: u128,
+    pub(crate) k: usize
 }
 
 impl MBucket {
 
-    pub fn new() -> Self {
+    pub fn new(k: usize) -> Self {
         Self {
             nodes: Vec::new(),
-            cache: Vec::new()
+            cache: Vec::new(),
+            pending: None,
+            last_changed: 0,
+            k
         }
     }
+}
+
+impl Bucket for MBucket {
+
+    fn get_nodes(&self) -> &Vec<Node> {
+        &self.nodes
+    }
+
+    fn get_nodes_mut(&mut self) -> &mut Vec<Node> {
+        &mut self.nodes
+    }
+
+    fn get_cache(&self) -> &Vec<Node> {
+        &self.cache
+    }
+
+    fn get_cache_mut(&mut self) -> &mut Vec<Node> {
+        &mut self.cache
+    }
+
+    fn get_pending(&self) -> Option<(Node, Node)> {
+        self.pending
+    }
+
+    fn set_pending(&mut self, pending: Option<(Node, Node)>) {
+        self.pending = pending;
+    }
+
+    fn get_k(&self) -> usize {
+        self.k
+    }
+
+    fn get_last_changed(&self) -> u128 {
+        self.last_changed
+    }
 
-    pub fn is_full(&self) -> bool {
-        self.nodes.len() >= MAX_BUCKET_SIZE
+    fn set_last_changed(&mut self, last_changed: u128) {
+        self.last_changed = last_changed;
     }
 }
@@ -1,21 +1,28 @@
 use std::any::Any;
+use std::io;
 use std::net::IpAddr;
 use std::sync::{Arc, Mutex};
-use crate::routing::inter::routing_table::{RestartListener, RoutingTable};
-use crate::utils;
-use crate::utils::hash::crc32c::Crc32c;
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::routing::inter::bucket::Bucket;
+use crate::routing::inter::routing_table::{derive_uid, find_closest, set_derived_uid, EvictionListener, RestartListener, RoutingTable};
 use crate::utils::linked_hashmap::LinkedHashMap;
 use crate::utils::net::address_utils::is_global_unicast;
-use crate::utils::node::{Node, V4_MASK, V6_MASK};
+use crate::utils::node::{Node, QUERY_TIME};
 use crate::utils::uid::{ID_LENGTH, UID};
+use super::m_bucket::{MBucket, MAX_BUCKET_SIZE};
 
 pub struct MRoutingTable {
     uid: Option<UID>,
+    uid_seed: Option<[u8; ID_LENGTH]>,
+    explicit_uid: Option<UID>,
     listeners: Vec<RestartListener>,
+    eviction_listeners: Vec<EvictionListener>,
     consensus_external_address: IpAddr,
     origin_pairs: LinkedHashMap<IpAddr, IpAddr>,
     secure_only: bool,
-    //m_buckets: [MBucket; ID_LENGTH*8]
+    k: usize,
+    query_time: u128,
+    m_buckets: Vec<MBucket>
 }
 
 impl MRoutingTable {
@@ -23,16 +30,95 @@ impl MRoutingTable {
     pub fn new() -> Self {
         let mut routing_table = Self {
             uid: None,
+            uid_seed: None,
+            explicit_uid: None,
             listeners: Vec::new(),
+            eviction_listeners: Vec::new(),
             consensus_external_address: IpAddr::from([127, 0, 1, 1]),
             origin_pairs: LinkedHashMap::with_capacity(64),
             secure_only: true,
-            //m_buckets: from_fn(|_| MBucket::new())
+            k: MAX_BUCKET_SIZE,
+            query_time: QUERY_TIME,
+            m_buckets: vec![MBucket::new(MAX_BUCKET_SIZE)]
         };
 
         routing_table.derive_uid();
         routing_table
     }
+
+    pub fn get_bucket_count(&self) -> usize {
+        self.m_buckets.len()
+    }
+
+    //BUCKET i HOLDS THE NODES SHARING EXACTLY i PREFIX BITS WITH US, THE LAST ONE EVERYTHING CLOSER INCLUDING OUR OWN ID
+    fn bucket_index(&self, k: &UID) -> usize {
+        let prefix = ID_LENGTH*8-self.uid.unwrap().distance(k);
+        prefix.min(self.m_buckets.len()-1)
+    }
+
+    fn split(&mut self) {
+        let bucket = self.m_buckets.pop().unwrap();
+        self.m_buckets.push(MBucket::new(self.k));
+        self.m_buckets.push(MBucket::new(self.k));
+
+        let len = self.m_buckets.len();
+        self.m_buckets[len-2].last_changed = bucket.last_changed;
+        self.m_buckets[len-1].last_changed = bucket.last_changed;
+
+        for node in bucket.nodes {
+            let id = self.bucket_index(&node.uid);
+            self.m_buckets[id].nodes.push(node);
+        }
+
+        for node in bucket.cache {
+            let id = self.bucket_index(&node.uid);
+            self.m_buckets[id].cache.push(node);
+        }
+
+        //A PENDING CHECK ONLY STILL MEANS SOMETHING WHEN THE NODE AND ITS CANDIDATE END UP IN THE SAME BUCKET
+        if let Some((oldest, candidate)) = bucket.pending {
+            let id = self.bucket_index(&oldest.uid);
+
+            if id == self.bucket_index(&candidate.uid) {
+                self.m_buckets[id].pending = bucket.pending;
+            }
+        }
+    }
+
+    //REBUILDING THE TABLE SKIPS THE EVICTION CHECKS, THOSE ONLY MAKE SENSE FOR NODES WE JUST HEARD FROM
+    fn insert_node(&mut self, n: Node, check_eviction: bool) -> Option<Node> {
+        if self.secure_only && !n.has_secure_id() {
+            return None;
+        }
+
+        if let Some(uid) = &self.uid {
+            if *uid != n.uid {
+                let contains_ip = self.m_buckets.iter().any(|b| b.contains_ip(&n));
+                let mut id = self.bucket_index(&n.uid);
+
+                if contains_ip != self.m_buckets[id].contains_uid(&n) {
+                    return None;
+                }
+
+                //ONLY THE BUCKET COVERING OUR OWN ID IS SPLIT WHEN FULL, THE OTHERS SEND NEW NODES TO THE REPLACEMENT CACHE
+                while !contains_ip && self.m_buckets[id].is_full() &&
+                        id == self.m_buckets.len()-1 && self.m_buckets.len() < ID_LENGTH*8 {
+                    self.split();
+                    id = self.bucket_index(&n.uid);
+                }
+
+                self.m_buckets[id].insert(n);
+
+                //A FULL BUCKET PINGS ITS LEAST RECENTLY SEEN NODE BEFORE THE CANDIDATE CAN REPLACE IT, WITHOUT
+                //A LISTENER NOTHING WOULD SEND THE PING SO NO CHECK IS STARTED
+                if check_eviction && !self.eviction_listeners.is_empty() {
+                    return self.m_buckets[id].check_eviction(n);
+                }
+            }
+        }
+
+        None
+    }
 }
 
 impl RoutingTable for MRoutingTable {
@@ -78,81 +164,62 @@ impl RoutingTable for MRoutingTable {
         self.consensus_external_address
     }
 
-    fn insert(&mut self, n: Node) {
-        if self.secure_only && !n.has_secure_id() {
-            return
-        }
-
-        if let Some(uid) = &self.uid {
-            if *uid != n.uid {
-                let id = self.bucket_uid(&n.uid);
-
-                //if self.m_bucket[id],is_full() {
-
-                //}
-
+    fn insert(&mut self, n: Node) -> Option<Node> {
+        self.insert_node(n, true)
+    }
 
-                /*
-                let mut contains_ip = false;
-                for b in &self.k_buckets {
-                    if b.contains_ip(&n) {
-                        contains_ip = true;
-                        break;
-                    }
-                }
+    fn insert_unchecked(&mut self, n: Node) {
+        self.insert_node(n, false);
+    }
 
-                let contains_uid = self.k_buckets[id].contains_uid(&n);
+    fn reset_buckets(&mut self, uid: UID) {
+        self.uid = Some(uid);
+        self.m_buckets = vec![MBucket::new(self.k)];
+    }
 
-                if contains_ip == contains_uid {
-                    self.k_buckets[id].insert(n);
-                }
-                */
+    fn mark_stale(&mut self, n: &Node) {
+        if let Some(uid) = &self.uid {
+            if *uid != n.uid {
+                let id = self.bucket_index(&n.uid);
+                self.m_buckets[id].mark_stale(n);
             }
         }
     }
 
     fn derive_uid(&mut self) {
-        let mut ip: Vec<u8> = match self.consensus_external_address {
-            IpAddr::V4(v4) => v4.octets().to_vec(),
-            IpAddr::V6(v6) => v6.octets().to_vec(),
-        };
-
-        let mask: Vec<u8> = if ip.len() == 4 {
-            V4_MASK.to_vec()
-        } else {
-            V6_MASK.to_vec()
-        };
-
-        for i in 0..mask.len() {
-            ip[i] &= mask[i];
-        }
+        self.uid = Some(derive_uid(self.explicit_uid, self.secure_only, self.consensus_external_address, self.uid_seed));
+    }
 
-        //let mut rng = rand::thread_rng();
-        let rand: u8 = /*rng.gen::<u8>()*/utils::random::gen::<u8>() & 0xFF;
-        let r = rand & 0x7;
+    fn get_derived_uid(&self) -> UID {
+        self.uid.unwrap()
+    }
 
-        ip[0] |= r << 5;
+    fn set_derived_uid(&mut self, uid: UID) {
+        set_derived_uid(self, uid);
+    }
 
-        let mut c = Crc32c::new();
-        c.update(&ip, 0, ip.len());
-        let crc = c.get_value();
+    fn set_uid_seed(&mut self, seed: [u8; ID_LENGTH]) {
+        self.uid_seed = Some(seed);
+        self.derive_uid();
+        self.set_derived_uid(self.get_derived_uid());
+    }
 
-        let mut bid = [0u8; ID_LENGTH];
-        bid[0] = (crc >> 24) as u8;
-        bid[1] = (crc >> 16) as u8;
-        bid[2] = ((crc >> 8) as u8 & 0xF8) | (/*rng.gen::<u8>()*/utils::random::gen::<u8>() & 0x7);
+    fn get_uid_seed(&self) -> Option<[u8; ID_LENGTH]> {
+        self.uid_seed
+    }
 
-        for i in 3..19 {
-            bid[i] = /*rng.gen::<u8>()*/utils::random::gen::<u8>() & 0xFF;
+    fn set_explicit_uid(&mut self, uid: UID) -> io::Result<()> {
+        if self.secure_only {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "An explicit ID requires secure_only to be off"));
         }
 
-        bid[19] = rand & 0xFF;
-
-        self.uid = Some(UID::from(bid));
+        self.explicit_uid = Some(uid);
+        self.set_derived_uid(uid);
+        Ok(())
     }
 
-    fn get_derived_uid(&self) -> UID {
-        self.uid.unwrap()
+    fn get_explicit_uid(&self) -> Option<UID> {
+        self.explicit_uid
     }
 
     fn is_secure_only(&self) -> bool {
@@ -163,6 +230,26 @@ impl RoutingTable for MRoutingTable {
         self.secure_only = secure_only;
     }
 
+    fn set_k(&mut self, k: usize) {
+        self.k = k.max(1);
+
+        for b in self.m_buckets.iter_mut() {
+            b.k = self.k;
+        }
+    }
+
+    fn get_k(&self) -> usize {
+        self.k
+    }
+
+    fn set_query_time(&mut self, query_time: u128) {
+        self.query_time = query_time;
+    }
+
+    fn get_query_time(&self) -> u128 {
+        self.query_time
+    }
+
     fn add_restart_listener(&mut self, listener: RestartListener) {
         self.listeners.push(listener);
     }
@@ -171,28 +258,79 @@ impl RoutingTable for MRoutingTable {
         let _ = self.listeners.remove(index);
     }
 
+    fn add_eviction_listener(&mut self, listener: EvictionListener) {
+        self.eviction_listeners.push(listener);
+    }
+
+    fn remove_eviction_listener(&mut self, index: usize) {
+        let _ = self.eviction_listeners.remove(index);
+    }
+
+    fn get_eviction_listeners(&self) -> Vec<EvictionListener> {
+        self.eviction_listeners.clone()
+    }
+
     fn has_queried(&self, n: &Node, now: u128) -> bool {
-        todo!()
+        let id = self.bucket_index(&n.uid);
+
+        if !self.m_buckets[id].contains_uid(n) {
+            return false;
+        }
+
+        self.m_buckets[id].has_queried(n, now, self.query_time)
     }
 
+    //SAME DISTANCE INDEX AS THE KADEMLIA TABLE, SO CALLERS CAN WALK 0..160 WITHOUT KNOWING HOW MANY BUCKETS EXIST
     fn bucket_uid(&self, k: &UID) -> usize {
-        todo!()
+        self.uid.unwrap().distance(k)-1
     }
 
     fn all_nodes(&self) -> Vec<Node> {
-        todo!()
+        let mut nodes = Vec::new();
+
+        for b in &self.m_buckets {
+            nodes.extend(&b.nodes);
+        }
+
+        nodes
     }
 
     fn find_closest(&self, k: &UID, r: usize) -> Vec<Node> {
-        todo!()
+        find_closest(self.all_nodes(), k, r, self.query_time)
     }
 
     fn bucket_size(&self, i: usize) -> usize {
-        todo!()
+        let prefix = (ID_LENGTH*8-1).saturating_sub(i);
+        self.m_buckets[prefix.min(self.m_buckets.len()-1)].nodes.len()
+    }
+
+    //MARKS THE FARTHEST BUCKET THAT HASNT CHANGED WITHIN idle_time AS REFRESHED AND RETURNS A RANDOM ID IN ITS RANGE
+    //BUCKET i COVERS THE IDS SHARING i PREFIX BITS WITH US, SO THE TARGET SITS AT DISTANCE 160-i
+    fn take_idle_bucket(&mut self, idle_time: u128) -> Option<UID> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_millis();
+
+        let uid = self.uid?;
+        let (i, bucket) = self.m_buckets.iter_mut().enumerate().find(|(_, b)| now.saturating_sub(b.get_last_changed()) >= idle_time)?;
+        bucket.touch();
+        Some(uid.generate_random_node_id_by_distance(ID_LENGTH*8-i))
     }
 
     fn all_unqueried_nodes(&self) -> Vec<Node> {
-        todo!()
+        let mut nodes = Vec::new();
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_millis();
+
+        for b in &self.m_buckets {
+            nodes.extend(&b.unqueried_nodes(now, self.query_time));
+        }
+
+        nodes
     }
 
     fn get_restart(&self) -> fn(Arc<Mutex<dyn RoutingTable>>) {
@@ -202,6 +340,15 @@ impl RoutingTable for MRoutingTable {
     fn restart(routing_table: Arc<Mutex<dyn RoutingTable>>) {
         routing_table.lock().unwrap().as_any_mut().downcast_mut::<Self>().unwrap().derive_uid();
 
+        //THE BUCKET LAYOUT DEPENDS ON OUR ID, SO THE TABLE IS REBUILT FROM SCRATCH
+        let nodes = routing_table.lock().unwrap().all_nodes();
+        let k = routing_table.lock().unwrap().get_k();
+        routing_table.lock().unwrap().as_any_mut().downcast_mut::<Self>().unwrap().m_buckets = vec![MBucket::new(k)];
+
+        for node in nodes {
+            routing_table.lock().unwrap().as_any_mut().downcast_mut::<Self>().unwrap().insert_node(node, false);
+        }
+
         if routing_table.lock().unwrap().as_any().downcast_ref::<Self>().unwrap().listeners.is_empty() {
             return;
         }
@@ -224,3 +371,123 @@ impl RoutingTable for MRoutingTable {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use std::net::SocketAddr;
+    use crate::utils;
+    use crate::routing::inter::{bucket, routing_table};
+    use super::*;
+
+    #[test]
+    fn bucket_splitting() {
+        let mut routing_table = MRoutingTable::new();
+        routing_table.set_secure_only(false);
+        let uid = routing_table.get_derived_uid();
+
+        for i in 0..1000u16 {
+            let mut bid = [0u8; ID_LENGTH];
+            bid.iter_mut().for_each(|b| *b = utils::random::gen::<u8>());
+            routing_table.insert(Node::new(UID::from(bid), SocketAddr::from(([10, 0, (i >> 8) as u8, i as u8], 6881))));
+        }
+
+        //THE NODE CLOSEST TO US ALWAYS FITS, OUR OWN BUCKET KEEPS SPLITTING
+        let mut bid = uid.bytes();
+        bid[ID_LENGTH-1] ^= 1;
+        let closest = Node::new(UID::from(bid), SocketAddr::from(([10, 1, 0, 1], 6881)));
+        routing_table.insert(closest);
+
+        assert!(routing_table.get_bucket_count() > 1);
+        assert!(routing_table.m_buckets.iter().all(|b| b.nodes.len() <= MAX_BUCKET_SIZE));
+        assert!(routing_table.find_closest(&uid, 1)[0] == closest);
+    }
+
+    #[test]
+    fn split_pending() {
+        let node = |uid: UID, flip: u8, i: u8| {
+            let mut bid = uid.bytes();
+            bid[0] ^= flip;
+            bid[ID_LENGTH-1] ^= i;
+            Node::new(UID::from(bid), SocketAddr::from(([10, 0, 0, i], 6881)))
+        };
+
+        //A CANDIDATE THAT LANDS NEXT TO THE NODE BEING CHECKED KEEPS THE CHECK, ONE THAT LANDS ELSEWHERE ENDS IT
+        for (flip, kept) in [(0x80, true), (0, false)] {
+            let mut routing_table = MRoutingTable::new();
+            routing_table.set_secure_only(false);
+            routing_table.set_k(2);
+            let uid = routing_table.get_derived_uid();
+
+            routing_table.insert(node(uid, 0x80, 1));
+            routing_table.insert(node(uid, 0x80, 2));
+            let candidate = node(uid, flip, 3);
+            routing_table.m_buckets[0].cache.push(candidate);
+            assert!(routing_table.m_buckets[0].check_eviction(candidate).is_some());
+
+            routing_table.split();
+            assert_eq!(routing_table.m_buckets[0].pending.is_some(), kept);
+            assert!(routing_table.m_buckets[1].pending.is_none());
+        }
+    }
+
+    #[test]
+    fn stale_promotion() {
+        bucket::tests::stale_promotion(&mut MBucket::new(2));
+    }
+
+    #[test]
+    fn cache_policy() {
+        bucket::tests::cache_policy(&mut MBucket::new(2));
+    }
+
+    #[test]
+    fn eviction_listener() {
+        routing_table::tests::eviction_listener(|| Arc::new(Mutex::new(MRoutingTable::new())));
+    }
+
+    #[test]
+    fn eviction_check() {
+        bucket::tests::eviction_check(&mut MBucket::new(2));
+    }
+
+    #[test]
+    fn node_states() {
+        routing_table::tests::node_states(|| Arc::new(Mutex::new(MRoutingTable::new())));
+    }
+
+    #[test]
+    fn stable_uid() {
+        let seed = [7u8; ID_LENGTH];
+
+        for address in [SocketAddr::from(([203, 0, 113, 7], 6881)), "[2001:db8::7]:6881".parse().unwrap()] {
+            let mut uids = Vec::new();
+
+            for _ in 0..2 {
+                let mut routing_table = MRoutingTable::new();
+                routing_table.set_uid_seed(seed);
+                routing_table.set_external_address(address.ip());
+                routing_table.derive_uid();
+                uids.push(routing_table.get_derived_uid());
+            }
+
+            assert!(uids[0] == uids[1]);
+            assert!(Node::new(uids[0], address).has_secure_id());
+        }
+
+        let mut routing_table = MRoutingTable::new();
+        let uid = UID::from([9u8; ID_LENGTH]);
+        assert!(routing_table.set_explicit_uid(uid).is_err());
+
+        routing_table.set_secure_only(false);
+        routing_table.set_explicit_uid(uid).unwrap();
+        routing_table.derive_uid();
+        assert!(routing_table.get_derived_uid() == uid);
+    }
+
+    #[test]
+    fn idle_buckets() {
+        routing_table::tests::idle_buckets(|| Arc::new(Mutex::new(MRoutingTable::new())));
+    }
+
+}
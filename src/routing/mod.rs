@@ -1,4 +1,6 @@
 pub mod inter;
 pub mod kb;
 pub mod mainline;
-pub mod bucket_types;
\ No newline at end of file
+pub mod bucket_types;
+pub mod routing_state;
+pub mod imported_state;
\ No newline at end of file
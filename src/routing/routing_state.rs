@@ -0,0 +1,146 @@
+use std::{fs, io};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use rlibbencode::variables::bencode_bytes::BencodeBytes;
+use rlibbencode::variables::bencode_object::{BencodeObject, GetObject, PutObject};
+use rlibbencode::variables::inter::bencode_variable::{FromBencode, ToBencode};
+use crate::routing::inter::routing_table::RoutingTable;
+use crate::utils::net::address_types::AddressTypes;
+use crate::utils::node::Node;
+use crate::utils::node_utils::{pack_nodes, unpack_nodes};
+use crate::utils::uid::{ID_LENGTH, UID};
+
+pub const STATE_ID_KEY: &str = "id";
+pub const STATE_NODES_KEY: &str = "nodes";
+pub const STATE_NODES6_KEY: &str = "nodes6";
+
+//BENCODED ROUTING TABLE STATE, THE NODES USE THE SAME COMPACT FORMAT AS find_node RESPONSES
+#[derive(Clone, Default, Debug)]
+pub struct RoutingState {
+    uid: Option<UID>,
+    nodes: Vec<Node>,
+    nodes6: Vec<Node>
+}
+
+impl RoutingState {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //THE TABLES ARE LOCKED ONE AT A TIME, THE ID IS THE ONE OF THE IPV4 TABLE
+    pub fn from_routing_tables(routing_table: &Arc<Mutex<dyn RoutingTable>>, routing_table6: &Arc<Mutex<dyn RoutingTable>>) -> Self {
+        let mut state = Self::new();
+
+        {
+            let routing_table = routing_table.lock().unwrap();
+            state.set_uid(routing_table.get_derived_uid());
+            state.add_nodes(routing_table.all_nodes());
+        }
+
+        state.add_nodes(routing_table6.lock().unwrap().all_nodes());
+        state
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::decode(&fs::read(path)?)
+    }
+
+    //WRITTEN TO A TEMPORARY FILE FIRST SO A CRASH MID WRITE NEVER LEAVES A TRUNCATED STATE FILE
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, self.encode())?;
+        fs::rename(&tmp, path)
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut ben = BencodeObject::new();
+
+        if let Some(uid) = &self.uid {
+            ben.put(STATE_ID_KEY, uid.bytes());
+        }
+
+        ben.put(STATE_NODES_KEY, pack_nodes(self.nodes.clone(), AddressTypes::Ipv4));
+        ben.put(STATE_NODES6_KEY, pack_nodes(self.nodes6.clone(), AddressTypes::Ipv6));
+        ben.to_bencode()
+    }
+
+    pub fn decode(buf: &[u8]) -> io::Result<Self> {
+        let ben = BencodeObject::from_bencode(buf).map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "State file is not valid bencode"))?;
+        let mut state = Self::new();
+
+        if let Some(id) = ben.get::<BencodeBytes>(STATE_ID_KEY) {
+            if id.as_bytes().len() != ID_LENGTH {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "State file id has the wrong length"));
+            }
+
+            let mut bid = [0u8; ID_LENGTH];
+            bid.copy_from_slice(id.as_bytes());
+            state.uid = Some(UID::from(bid));
+        }
+
+        if let Some(nodes) = ben.get::<BencodeBytes>(STATE_NODES_KEY) {
+            state.nodes = unpack_nodes(nodes.as_bytes(), AddressTypes::Ipv4);
+        }
+
+        if let Some(nodes6) = ben.get::<BencodeBytes>(STATE_NODES6_KEY) {
+            state.nodes6 = unpack_nodes(nodes6.as_bytes(), AddressTypes::Ipv6);
+        }
+
+        Ok(state)
+    }
+
+    pub fn set_uid(&mut self, uid: UID) {
+        self.uid = Some(uid);
+    }
+
+    pub fn get_uid(&self) -> Option<UID> {
+        self.uid
+    }
+
+    //NODES ARE SORTED INTO nodes OR nodes6 BY THEIR ADDRESS FAMILY
+    pub fn add_nodes(&mut self, nodes: Vec<Node>) {
+        for node in nodes {
+            match node.address.is_ipv4() {
+                true => self.nodes.push(node),
+                false => self.nodes6.push(node)
+            }
+        }
+    }
+
+    pub fn get_nodes(&self) -> &Vec<Node> {
+        &self.nodes
+    }
+
+    pub fn get_nodes6(&self) -> &Vec<Node> {
+        &self.nodes6
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::net::SocketAddr;
+    use super::*;
+
+    #[test]
+    fn state_round_trip() {
+        let mut state = RoutingState::new();
+        state.set_uid(UID::from([1u8; ID_LENGTH]));
+        state.add_nodes(vec![
+            Node::new(UID::from([2u8; ID_LENGTH]), SocketAddr::from(([10, 0, 0, 1], 6881))),
+            Node::new(UID::from([3u8; ID_LENGTH]), "[2001:db8::1]:6882".parse().unwrap())
+        ]);
+
+        let decoded = RoutingState::decode(&state.encode()).unwrap();
+        assert!(decoded.get_uid() == Some(UID::from([1u8; ID_LENGTH])));
+        assert_eq!(decoded.get_nodes().len(), 1);
+        assert_eq!(decoded.get_nodes6().len(), 1);
+        assert_eq!(decoded.get_nodes()[0].address, SocketAddr::from(([10, 0, 0, 1], 6881)));
+        assert_eq!(decoded.get_nodes6()[0].address, "[2001:db8::1]:6882".parse::<SocketAddr>().unwrap());
+        assert!(decoded.get_nodes6()[0].uid == UID::from([3u8; ID_LENGTH]));
+
+        assert!(RoutingState::decode(b"d2:id3:abce").is_err());
+    }
+}
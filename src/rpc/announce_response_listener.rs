@@ -0,0 +1,72 @@
+use std::sync::{Arc, Mutex};
+use crate::routing::inter::routing_table::{insert_checked, RoutingTable};
+use crate::rpc::events::error_response_event::ErrorResponseEvent;
+use crate::rpc::events::inter::message_event::MessageEvent;
+use crate::rpc::events::inter::response_callback::ResponseCallback;
+use crate::rpc::events::response_event::ResponseEvent;
+use crate::rpc::events::stalled_event::StalledEvent;
+use crate::rpc::query_future::QueryPromise;
+use crate::utils::node::Node;
+
+struct AnnounceState {
+    pending: usize,
+    announced: Vec<Node>
+}
+
+//COUNTS DOWN THE ANNOUNCE_PEER QUERIES, RESOLVES WITH EVERY NODE THAT ACCEPTED THE ANNOUNCE
+#[derive(Clone)]
+pub struct AnnounceResponseListener {
+    routing_table: Arc<Mutex<dyn RoutingTable>>,
+    promise: QueryPromise<Vec<Node>>,
+    state: Arc<Mutex<AnnounceState>>
+}
+
+impl AnnounceResponseListener {
+
+    pub fn new(routing_table: Arc<Mutex<dyn RoutingTable>>, promise: QueryPromise<Vec<Node>>, pending: usize) -> Self {
+        Self {
+            routing_table,
+            promise,
+            state: Arc::new(Mutex::new(AnnounceState {
+                pending,
+                announced: Vec::new()
+            }))
+        }
+    }
+
+    pub fn complete(&self, node: Option<Node>) {
+        let announced = {
+            let mut state = self.state.lock().unwrap();
+
+            if let Some(node) = node {
+                state.announced.push(node);
+            }
+
+            state.pending = state.pending.saturating_sub(1);
+
+            if state.pending > 0 {
+                return;
+            }
+
+            state.announced.clone()
+        };
+
+        self.promise.complete(Ok(announced));
+    }
+}
+
+impl ResponseCallback for AnnounceResponseListener {
+
+    fn on_response(&self, _event: ResponseEvent) {
+        insert_checked(&self.routing_table, _event.get_node());
+        self.complete(Some(_event.get_node()));
+    }
+
+    fn on_error_response(&self, _event: ErrorResponseEvent) {
+        self.complete(None);
+    }
+
+    fn on_stalled(&self, _event: StalledEvent) {
+        self.complete(None);
+    }
+}
@@ -1,7 +1,6 @@
 use std::time::{SystemTime, UNIX_EPOCH};
 use crate::messages::inter::method_message_base::MethodMessageBase;
 use crate::rpc::events::inter::response_callback::ResponseCallback;
-use crate::rpc::response_tracker::STALLED_TIME;
 use crate::utils::node::Node;
 
 pub struct Call {
@@ -59,7 +58,7 @@ impl Call {
         self.sent_time
     }
 
-    pub fn is_stalled(&self, now: u128) -> bool {
-        now-self.sent_time > STALLED_TIME
+    pub fn is_stalled(&self, now: u128, stalled_time: u128) -> bool {
+        now-self.sent_time > stalled_time
     }
 }
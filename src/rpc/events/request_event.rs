@@ -1,6 +1,7 @@
 use std::ops::DerefMut;
 use std::time::{SystemTime, UNIX_EPOCH};
 use crate::messages::inter::message_base::MessageBase;
+use crate::messages::inter::message_exception::MessageException;
 use crate::rpc::events::inter::event::Event;
 use crate::rpc::events::inter::message_event::MessageEvent;
 use crate::utils::node::Node;
@@ -10,7 +11,9 @@ pub struct RequestEvent<'a> {
     message: &'a dyn MessageBase,
     node: Option<Node>,
     received_time: u128,
-    response: Option<Box<dyn MessageBase>>
+    version: Option<Vec<u8>>,
+    response: Option<Box<dyn MessageBase>>,
+    exception: Option<MessageException>
 }
 
 impl<'a> RequestEvent<'a> {
@@ -21,10 +24,20 @@ impl<'a> RequestEvent<'a> {
             message,
             node: None,
             received_time: 0,
-            response: None
+            version: None,
+            response: None,
+            exception: None
         }
     }
 
+    pub fn set_version(&mut self, version: &[u8]) {
+        self.version = Some(version.to_vec());
+    }
+
+    pub fn get_version(&self) -> Option<&Vec<u8>> {
+        self.version.as_ref()
+    }
+
     pub fn has_response(&self) -> bool {
         self.response.is_some()
     }
@@ -36,6 +49,18 @@ impl<'a> RequestEvent<'a> {
     pub fn set_response(&mut self, message: Box<dyn MessageBase>) {
         self.response = Some(message);
     }
+
+    pub fn has_exception(&self) -> bool {
+        self.exception.is_some()
+    }
+
+    pub fn take_exception(&mut self) -> Option<MessageException> {
+        self.exception.take()
+    }
+
+    pub fn set_exception(&mut self, exception: MessageException) {
+        self.exception = Some(exception);
+    }
 }
 
 impl<'a> Event for RequestEvent<'a> {
@@ -10,6 +10,7 @@ pub struct ResponseEvent<'a> {
     node: Option<Node>,
     received_time: u128,
     sent_time: u128,
+    version: Option<Vec<u8>>,
     request: Option<&'a dyn MessageBase>
 }
 
@@ -22,10 +23,19 @@ impl<'a> ResponseEvent<'a> {
             node: Some(node),
             received_time: 0,
             sent_time: 0,
+            version: None,
             request: None
         }
     }
 
+    pub fn set_version(&mut self, version: &[u8]) {
+        self.version = Some(version.to_vec());
+    }
+
+    pub fn get_version(&self) -> Option<&Vec<u8>> {
+        self.version.as_ref()
+    }
+
     pub fn has_request(&self) -> bool {
         self.request.is_some()
     }
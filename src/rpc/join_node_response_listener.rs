@@ -1,12 +1,11 @@
-use std::sync::{Arc, Mutex};
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::Arc;
 use crate::kad::kademlia_base::KademliaBase;
-use crate::messages::find_node_request::FindNodeRequest;
+use crate::lookup::find_node_lookup_handler::FindNodeLookupHandler;
+use crate::lookup::iterative_lookup::IterativeLookup;
 use crate::messages::find_node_response::FindNodeResponse;
 use crate::messages::inter::message_base::MessageBase;
 use crate::messages::ping_request::PingRequest;
-use crate::routing::kb::k_comparator::KComparator;
+use crate::routing::inter::routing_table::insert_checked;
 use crate::rpc::events::inter::message_event::MessageEvent;
 use crate::rpc::events::inter::response_callback::ResponseCallback;
 use crate::rpc::events::response_event::ResponseEvent;
@@ -15,18 +14,14 @@ use crate::utils::node::Node;
 
 #[derive(Clone)]
 pub struct JoinNodeResponseListener {
-    kademlia: Box<dyn KademliaBase>,
-    queries: Arc<Mutex<Vec<Node>>>,
-    stop: Arc<AtomicBool>
+    kademlia: Box<dyn KademliaBase>
 }
 
 impl JoinNodeResponseListener {
 
     pub fn new(kademlia: &dyn KademliaBase) -> Self {
         Self {
-            kademlia: kademlia.clone_dyn(),
-            queries: Arc::new(Mutex::new(Vec::new())),
-            stop: Arc::new(AtomicBool::new(false))
+            kademlia: kademlia.clone_dyn()
         }
     }
 }
@@ -34,65 +29,38 @@ impl JoinNodeResponseListener {
 impl ResponseCallback for JoinNodeResponseListener {
 
     fn on_response(&self, _event: ResponseEvent) {
-        self.kademlia.get_routing_table().lock().unwrap().insert(_event.get_node());
+        let routing_table = self.kademlia.get_routing_table_for(&_event.get_node().address).clone();
+        insert_checked(&routing_table, _event.get_node());
         println!("JOINED {}", _event.get_node().to_string());
 
         let response = _event.get_message().as_any().downcast_ref::<FindNodeResponse>().unwrap();
 
-        if response.has_nodes() {
-            let mut nodes = response.get_all_nodes();
+        //NODES FROM THE OTHER FAMILY ONLY SEED THEIR OWN TABLE, THE LOOKUP STAYS ON THE RESPONDERS FAMILY
+        let (nodes, other): (Vec<Node>, Vec<Node>) = response.get_all_nodes().into_iter()
+            .partition(|node| node.address.is_ipv4() == _event.get_node().address.is_ipv4());
 
-            let now = SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .expect("Time went backwards")
-                .as_millis();
-            let uid = self.kademlia.get_routing_table().lock().unwrap().get_derived_uid();
-            let distance = uid.distance(&_event.get_node().uid);
+        if !other.is_empty() && self.kademlia.get_server().lock().unwrap().is_dual_stack() {
+            let listener = PingResponseListener::new(self.kademlia.get_routing_table_for(&other[0].address).clone());
 
-            let comparator = KComparator::new(&uid);
-            nodes.sort_by(|a, b| comparator.compare(a, b));
-
-            nodes.retain(|node| {
-                if uid == node.uid ||
-                        self.queries.lock().unwrap().contains(node) ||
-                        self.kademlia.get_routing_table().lock().unwrap().has_queried(node, now) {
-                    false
-
-                } else {
-                    true
-                }
-            });
-
-            for node in &nodes {
-                self.queries.lock().unwrap().push(node.clone());
-            }
-
-            if self.stop.load(Ordering::Relaxed) || nodes.is_empty() || distance <= uid.distance(&nodes.get(0).unwrap().uid) {
-                self.stop.store(true, Ordering::Relaxed);
-
-                let listener = PingResponseListener::new(self.kademlia.get_routing_table().clone());
-
-                for node in nodes {
-                    let mut request = PingRequest::default();
-                    request.set_destination(node.address);
+            for node in other {
+                let mut request = PingRequest::default();
+                request.set_destination(node.address);
 
-                    self.kademlia.get_server().lock().unwrap().send_with_node_callback(&mut request, node, Box::new(listener.clone())).expect("Cannot send request");
+                if let Err(e) = self.kademlia.get_server().lock().unwrap().send_with_node_callback(&mut request, node, Box::new(listener.clone())) {
+                    println!("{}", e);
                 }
-
-                return;
             }
+        }
 
-            for node in nodes {
-                let mut request = FindNodeRequest::default();
-                request.set_destination(node.address);
-                request.set_target(self.kademlia.get_routing_table().lock().unwrap().get_derived_uid());
+        //THE BOOTSTRAP NODE ONLY SEEDS THE SHORTLIST, THE LOOKUP FOR OUR OWN ID FILLS THE TABLE
+        let uid = routing_table.lock().unwrap().get_derived_uid();
+        let lookup = IterativeLookup::new(self.kademlia.as_ref(), routing_table.clone(), uid, Arc::new(FindNodeLookupHandler::new(routing_table.clone(), uid)));
+        lookup.add_nodes(nodes);
 
-                self.kademlia.get_server().lock().unwrap().send_with_node_callback(&mut request, node, Box::new(self.clone())).expect("Cannot send request");
-            }
+        if let Err(e) = lookup.start() {
+            println!("{}", e);
         }
 
-        if !self.kademlia.get_refresh_handler().lock().unwrap().is_running() {
-            self.kademlia.get_refresh_handler().lock().unwrap().start();
-        }
+        self.kademlia.get_refresh_handler().lock().unwrap().resume();
     }
 }
@@ -3,3 +3,6 @@ pub mod response_tracker;
 pub mod call;
 pub mod join_node_response_listener;
 pub mod ping_response_listener;
+pub mod query_future;
+pub mod query_response_listener;
+pub mod announce_response_listener;
@@ -1,5 +1,5 @@
 use std::sync::{Arc, Mutex};
-use crate::routing::inter::routing_table::RoutingTable;
+use crate::routing::inter::routing_table::{insert_checked, RoutingTable};
 use crate::rpc::events::inter::message_event::MessageEvent;
 use crate::rpc::events::inter::response_callback::ResponseCallback;
 use crate::rpc::events::response_event::ResponseEvent;
@@ -22,12 +22,12 @@ impl PingResponseListener {
 impl ResponseCallback for PingResponseListener {
 
     fn on_response(&self, _event: ResponseEvent) {
-        self.routing_table.lock().unwrap().insert(_event.get_node());
+        insert_checked(&self.routing_table, _event.get_node());
     }
 
     fn on_stalled(&self, _event: StalledEvent) {
         if _event.has_node() {
-            _event.get_node().mark_stale(); //WILL THIS ACT CORRECTLY...? - THIS GOES FOR JAVA AS WELL...
+            self.routing_table.lock().unwrap().mark_stale(&_event.get_node());
         }
     }
 }
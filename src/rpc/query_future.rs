@@ -0,0 +1,168 @@
+use std::{fmt, io};
+use std::fmt::Formatter;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+use crate::messages::inter::message_exception::MessageException;
+
+#[derive(Debug)]
+pub enum QueryError {
+    ErrorResponse(MessageException),
+    Timeout,
+    Io(io::Error)
+}
+
+impl fmt::Display for QueryError {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ErrorResponse(e) => write!(f, "Error response {}: {}", e.get_code(), e.get_message()),
+            Self::Timeout => write!(f, "Query timed out"),
+            Self::Io(e) => write!(f, "{}", e)
+        }
+    }
+}
+
+impl From<io::Error> for QueryError {
+
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+struct QueryState<T> {
+    result: Option<Result<T, QueryError>>,
+    completed: bool,
+    waker: Option<Waker>
+}
+
+struct QueryInner<T> {
+    state: Mutex<QueryState<T>>,
+    condvar: Condvar
+}
+
+//RESOLVED BY THE RESPONSE CALLBACKS, CAN BE BLOCKED ON WITH wait OR AWAITED AS A FUTURE
+//NEVER BLOCK ON IT FROM INSIDE A CALLBACK, THE CALLBACK THAT WOULD RESOLVE IT RUNS ON THE SAME THREAD
+pub struct QueryFuture<T> {
+    inner: Arc<QueryInner<T>>
+}
+
+#[derive(Clone)]
+pub struct QueryPromise<T> {
+    inner: Arc<QueryInner<T>>
+}
+
+pub fn query_channel<T>() -> (QueryFuture<T>, QueryPromise<T>) {
+    let inner = Arc::new(QueryInner {
+        state: Mutex::new(QueryState {
+            result: None,
+            completed: false,
+            waker: None
+        }),
+        condvar: Condvar::new()
+    });
+
+    (QueryFuture { inner: inner.clone() }, QueryPromise { inner })
+}
+
+impl<T> QueryFuture<T> {
+
+    pub fn is_done(&self) -> bool {
+        self.inner.state.lock().unwrap().completed
+    }
+
+    pub fn wait(self) -> Result<T, QueryError> {
+        let mut state = self.inner.state.lock().unwrap();
+
+        while !state.completed {
+            state = self.inner.condvar.wait(state).unwrap();
+        }
+
+        state.result.take().unwrap()
+    }
+
+    pub fn wait_timeout(self, timeout: Duration) -> Result<T, QueryError> {
+        let deadline = Instant::now()+timeout;
+        let mut state = self.inner.state.lock().unwrap();
+
+        while !state.completed {
+            let now = Instant::now();
+
+            if now >= deadline {
+                return Err(QueryError::Timeout);
+            }
+
+            state = self.inner.condvar.wait_timeout(state, deadline-now).unwrap().0;
+        }
+
+        state.result.take().unwrap()
+    }
+}
+
+impl<T> Future for QueryFuture<T> {
+
+    type Output = Result<T, QueryError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.inner.state.lock().unwrap();
+
+        if state.completed {
+            return Poll::Ready(state.result.take().unwrap());
+        }
+
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl<T> QueryPromise<T> {
+
+    //ONLY THE FIRST RESULT IS KEPT, LATE RESPONSES AND STALLS AFTER THAT ARE IGNORED
+    pub fn complete(&self, result: Result<T, QueryError>) {
+        let waker = {
+            let mut state = self.inner.state.lock().unwrap();
+
+            if state.completed {
+                return;
+            }
+
+            state.result = Some(result);
+            state.completed = true;
+            state.waker.take()
+        };
+
+        self.inner.condvar.notify_all();
+
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    pub fn is_completed(&self) -> bool {
+        self.inner.state.lock().unwrap().completed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::thread;
+    use std::time::Duration;
+    use super::*;
+
+    #[test]
+    fn query_future() {
+        let (future, _promise) = query_channel::<u32>();
+        assert!(matches!(future.wait_timeout(Duration::from_millis(10)), Err(QueryError::Timeout)));
+
+        let (future, promise) = query_channel::<u32>();
+        thread::spawn(move || {
+            promise.complete(Ok(7));
+            promise.complete(Ok(8));
+        });
+
+        assert_eq!(future.wait().unwrap(), 7);
+    }
+}
@@ -0,0 +1,49 @@
+use std::any::Any;
+use std::sync::{Arc, Mutex};
+use crate::messages::error_response::ErrorResponse;
+use crate::messages::inter::message_exception::MessageException;
+use crate::routing::inter::routing_table::{insert_checked, RoutingTable};
+use crate::rpc::events::error_response_event::ErrorResponseEvent;
+use crate::rpc::events::inter::message_event::MessageEvent;
+use crate::rpc::events::inter::response_callback::ResponseCallback;
+use crate::rpc::events::response_event::ResponseEvent;
+use crate::rpc::events::stalled_event::StalledEvent;
+use crate::rpc::query_future::{QueryError, QueryPromise};
+
+//RESOLVES A SINGLE QUERY WITH THE RESPONSE, THE ERROR RESPONSE OR A TIMEOUT ONCE THE TRACKER STALLS THE CALL
+#[derive(Clone)]
+pub struct QueryResponseListener<T> {
+    routing_table: Arc<Mutex<dyn RoutingTable>>,
+    promise: QueryPromise<T>
+}
+
+impl<T> QueryResponseListener<T> {
+
+    pub fn new(routing_table: Arc<Mutex<dyn RoutingTable>>, promise: QueryPromise<T>) -> Self {
+        Self {
+            routing_table,
+            promise
+        }
+    }
+}
+
+impl<T: Any + Clone + Send> ResponseCallback for QueryResponseListener<T> {
+
+    fn on_response(&self, _event: ResponseEvent) {
+        insert_checked(&self.routing_table, _event.get_node());
+
+        match _event.get_message().as_any().downcast_ref::<T>() {
+            Some(response) => self.promise.complete(Ok(response.clone())),
+            None => self.promise.complete(Err(QueryError::ErrorResponse(MessageException::new("Method Unknown", 204))))
+        }
+    }
+
+    fn on_error_response(&self, _event: ErrorResponseEvent) {
+        let response = _event.get_message().as_any().downcast_ref::<ErrorResponse>().unwrap();
+        self.promise.complete(Err(QueryError::ErrorResponse(MessageException::new(response.get_description(), response.get_code()))));
+    }
+
+    fn on_stalled(&self, _event: StalledEvent) {
+        self.promise.complete(Err(QueryError::Timeout));
+    }
+}
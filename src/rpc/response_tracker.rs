@@ -1,15 +1,13 @@
 use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
-use crate::kad::server::TID_LENGTH;
 use crate::rpc::call::Call;
-use crate::rpc::events::inter::message_event::MessageEvent;
-use crate::rpc::events::stalled_event::StalledEvent;
 
 pub const MAX_ACTIVE_CALLS: usize = 512;
 pub const STALLED_TIME: u128 = 60000;
 
 pub struct ResponseTracker {
-    calls: HashMap<[u8; TID_LENGTH], Call>
+    calls: HashMap<Vec<u8>, Call>,
+    stalled_time: u128
 }
 
 impl ResponseTracker {
@@ -17,58 +15,52 @@ impl ResponseTracker {
     pub fn new() -> Self {
         Self {
             calls: HashMap::with_capacity(MAX_ACTIVE_CALLS),
+            stalled_time: STALLED_TIME
         }
     }
 
-    pub fn add(&mut self, tid: [u8; TID_LENGTH], call: Call) {
-        self.calls.insert(tid, call);
+    pub fn set_stalled_time(&mut self, stalled_time: u128) {
+        self.stalled_time = stalled_time;
     }
 
-    pub fn get(&self, tid: &[u8; TID_LENGTH]) -> Option<&Call> {
+    pub fn get_stalled_time(&self) -> u128 {
+        self.stalled_time
+    }
+
+    pub fn add(&mut self, tid: &[u8], call: Call) {
+        self.calls.insert(tid.to_vec(), call);
+    }
+
+    pub fn get(&self, tid: &[u8]) -> Option<&Call> {
         self.calls.get(tid)
     }
 
-    pub fn contains(&self, tid: &[u8; TID_LENGTH]) -> bool {
+    pub fn contains(&self, tid: &[u8]) -> bool {
         self.calls.contains_key(tid)
     }
 
-    pub fn remove(&mut self, tid: &[u8; TID_LENGTH]) -> Option<Call> {
+    pub fn remove(&mut self, tid: &[u8]) -> Option<Call> {
         self.calls.remove(tid)
     }
 
-    pub fn poll(&mut self, tid: &[u8; TID_LENGTH]) -> Option<Call> {
+    pub fn poll(&mut self, tid: &[u8]) -> Option<Call> {
         self.calls.remove(tid)
     }
 
-    pub fn remove_stalled(&mut self) {
+    pub fn remove_stalled(&mut self) -> Vec<Call> {
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .expect("Time went backwards")
             .as_millis();
 
-        let mut stalled = Vec::new();
-
-        for (&tid, call) in self.calls.iter() {
-            if !call.is_stalled(now) {
-                break;
-            }
+        let stalled: Vec<Vec<u8>> = self.calls.iter()
+            .filter(|(_, call)| call.is_stalled(now, self.stalled_time))
+            .map(|(tid, _)| tid.clone())
+            .collect();
 
-            stalled.push(tid);
-        }
-
-        for tid in stalled {
-            if let Some(call) = self.calls.remove(&tid) {
-                //println!("STALLED {}", call.get_node().to_string());
-
-                let mut event = StalledEvent::new(call.get_message().upcast());
-                event.set_sent_time(call.get_sent_time());
-
-                if call.has_node() {
-                    event.set_node(call.get_node());
-                }
-
-                call.get_response_callback().on_stalled(event);
-            }
-        }
+        //CALLBACKS ARE LEFT TO THE CALLER SO THEY CAN RUN WITHOUT THE SERVER LOCK HELD
+        stalled.iter()
+            .filter_map(|tid| self.calls.remove(tid))
+            .collect()
     }
 }
@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::utils::ed25519::{PUBLIC_KEY_LENGTH, SIGNATURE_LENGTH};
+use crate::utils::hash::sha1::Sha1;
+use crate::utils::uid::UID;
+
+pub const MAX_ITEMS: usize = 2048;
+pub const MAX_VALUE_SIZE: usize = 1000;
+pub const MAX_SALT_SIZE: usize = 64;
+pub const ITEM_EXPIRE_TIME: u128 = 7200000;
+
+#[derive(Debug, Clone)]
+pub struct StoredItem {
+    pub(crate) value: Vec<u8>,
+    pub(crate) key: Option<[u8; PUBLIC_KEY_LENGTH]>,
+    pub(crate) salt: Option<Vec<u8>>,
+    pub(crate) signature: Option<[u8; SIGNATURE_LENGTH]>,
+    pub(crate) seq: i64,
+    pub(crate) stored: u128
+}
+
+impl StoredItem {
+
+    pub fn get_value(&self) -> &Vec<u8> {
+        &self.value
+    }
+
+    pub fn is_mutable(&self) -> bool {
+        self.key.is_some()
+    }
+
+    pub fn get_key(&self) -> Option<&[u8; PUBLIC_KEY_LENGTH]> {
+        self.key.as_ref()
+    }
+
+    pub fn get_salt(&self) -> Option<&Vec<u8>> {
+        self.salt.as_ref()
+    }
+
+    pub fn get_signature(&self) -> Option<&[u8; SIGNATURE_LENGTH]> {
+        self.signature.as_ref()
+    }
+
+    pub fn get_seq(&self) -> i64 {
+        self.seq
+    }
+
+    pub fn get_stored_time(&self) -> u128 {
+        self.stored
+    }
+}
+
+pub struct ItemStore {
+    items: HashMap<UID, StoredItem>
+}
+
+impl ItemStore {
+
+    pub fn new() -> Self {
+        Self {
+            items: HashMap::new()
+        }
+    }
+
+    pub fn put(&mut self, target: UID, value: Vec<u8>) {
+        self.insert(target, StoredItem {
+            value,
+            key: None,
+            salt: None,
+            signature: None,
+            seq: 0,
+            stored: 0
+        });
+    }
+
+    pub fn put_mutable(&mut self, target: UID, key: [u8; PUBLIC_KEY_LENGTH], salt: Option<Vec<u8>>, signature: [u8; SIGNATURE_LENGTH], seq: i64, value: Vec<u8>) {
+        self.insert(target, StoredItem {
+            value,
+            key: Some(key),
+            salt,
+            signature: Some(signature),
+            seq,
+            stored: 0
+        });
+    }
+
+    fn insert(&mut self, target: UID, mut item: StoredItem) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_millis();
+
+        if !self.items.contains_key(&target) && self.items.len() >= MAX_ITEMS {
+            self.remove_expired(now);
+
+            if self.items.len() >= MAX_ITEMS {
+                //EVICT THE ITEM THAT HAS GONE THE LONGEST WITHOUT BEING STORED AGAIN
+                let oldest = self.items.iter()
+                    .min_by_key(|(_, item)| item.stored)
+                    .map(|(k, _)| *k)
+                    .unwrap();
+                self.items.remove(&oldest);
+            }
+        }
+
+        item.stored = now;
+        self.items.insert(target, item);
+    }
+
+    pub fn get(&self, target: &UID) -> Option<&StoredItem> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_millis();
+
+        self.items.get(target).filter(|item| now - item.stored < ITEM_EXPIRE_TIME)
+    }
+
+    pub fn contains(&self, target: &UID) -> bool {
+        self.get(target).is_some()
+    }
+
+    pub fn remove_expired(&mut self, now: u128) {
+        self.items.retain(|_, item| now - item.stored < ITEM_EXPIRE_TIME);
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+impl Default for ItemStore {
+
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn mutable_target(key: &[u8], salt: Option<&[u8]>) -> UID {
+    let mut hash = Sha1::new();
+    hash.update(key, 0, key.len());
+
+    if let Some(salt) = salt {
+        hash.update(salt, 0, salt.len());
+    }
+
+    UID::from(hash.get_value())
+}
+
+//THE SIGNATURE COVERS THE BENCODED SALT, SEQ AND V ENTRIES WITHOUT THE OUTER DICTIONARY
+pub fn signature_buffer(salt: Option<&[u8]>, seq: i64, value: &[u8]) -> Vec<u8> {
+    let mut buf = Vec::new();
+
+    if let Some(salt) = salt.filter(|salt| !salt.is_empty()) {
+        buf.extend_from_slice(format!("4:salt{}:", salt.len()).as_bytes());
+        buf.extend_from_slice(salt);
+    }
+
+    buf.extend_from_slice(format!("3:seqi{}e1:v", seq).as_bytes());
+    buf.extend_from_slice(value);
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{mutable_target, signature_buffer};
+
+    fn unhex(s: &str) -> Vec<u8> {
+        (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+    }
+
+    #[test]
+    fn bep44_vectors() {
+        let key = unhex("77ff84905a91936367c01360803104f92432fcd904a43511876df5cdf3e7e548");
+
+        assert_eq!(mutable_target(&key, None).bytes().to_vec(), unhex("4a533d47ec9c7d95b1ad75f576cffc641853b750"));
+        assert_eq!(mutable_target(&key, Some(b"foobar")).bytes().to_vec(), unhex("411eba73b6f087ca51a3795d9c8c938d365e32c1"));
+
+        assert_eq!(signature_buffer(None, 1, b"12:Hello World!"), b"3:seqi1e1:v12:Hello World!");
+        assert_eq!(signature_buffer(Some(b"foobar"), 1, b"12:Hello World!"), b"4:salt6:foobar3:seqi1e1:v12:Hello World!");
+    }
+}
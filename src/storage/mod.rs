@@ -0,0 +1,2 @@
+pub mod peer_store;
+pub mod item_store;
@@ -0,0 +1,211 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::utils::bloom_filter::BloomFilter;
+use crate::utils::random::gen;
+use crate::utils::uid::UID;
+
+pub const MAX_PEERS_PER_HASH: usize = 200;
+pub const MAX_INFO_HASHES: usize = 4096;
+pub const MAX_VALUES: usize = 50;
+pub const PEER_EXPIRE_TIME: u128 = 1800000;
+pub const MAX_SAMPLES: usize = 20;
+pub const SAMPLE_INTERVAL: u128 = 300000;
+
+#[derive(Debug, Copy, Clone)]
+pub struct StoredPeer {
+    pub(crate) address: SocketAddr,
+    pub(crate) announced: u128,
+    pub(crate) seed: bool
+}
+
+pub struct PeerStore {
+    peers: HashMap<UID, Vec<StoredPeer>>,
+    samples: Vec<UID>,
+    last_sample: u128
+}
+
+impl PeerStore {
+
+    pub fn new() -> Self {
+        Self {
+            peers: HashMap::new(),
+            samples: Vec::new(),
+            last_sample: 0
+        }
+    }
+
+    pub fn add_peer(&mut self, info_hash: UID, address: SocketAddr, seed: bool) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_millis();
+
+        if !self.peers.contains_key(&info_hash) && self.peers.len() >= MAX_INFO_HASHES {
+            self.remove_expired(now);
+
+            if self.peers.len() >= MAX_INFO_HASHES {
+                return;
+            }
+        }
+
+        let peers = self.peers.entry(info_hash).or_default();
+
+        if let Some(peer) = peers.iter_mut().find(|p| p.address == address) {
+            peer.announced = now;
+            peer.seed = seed;
+            return;
+        }
+
+        if peers.len() >= MAX_PEERS_PER_HASH {
+            //REPLACE THE PEER THAT HAS GONE THE LONGEST WITHOUT ANNOUNCING
+            let oldest = peers.iter()
+                .enumerate()
+                .min_by_key(|(_, p)| p.announced)
+                .map(|(i, _)| i)
+                .unwrap();
+            peers.remove(oldest);
+        }
+
+        peers.push(StoredPeer {
+            address,
+            announced: now,
+            seed
+        });
+    }
+
+    pub fn get_peers(&self, info_hash: &UID, max: usize, no_seed: bool) -> Vec<SocketAddr> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_millis();
+
+        match self.peers.get(info_hash) {
+            Some(peers) => {
+                let mut peers: Vec<&StoredPeer> = peers.iter()
+                    .filter(|p| now.saturating_sub(p.announced) < PEER_EXPIRE_TIME && !(no_seed && p.seed))
+                    .collect();
+                peers.sort_by_key(|p| Reverse(p.announced));
+                peers.iter().take(max).map(|p| p.address).collect()
+            }
+            None => Vec::new()
+        }
+    }
+
+    //RETURNS THE (BFsd, BFpe) SCRAPE FILTERS, SEEDS AND DOWNLOADERS ARE KEPT IN SEPARATE FILTERS
+    pub fn get_scrape(&self, info_hash: &UID) -> (BloomFilter, BloomFilter) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_millis();
+
+        let mut seeds = BloomFilter::new();
+        let mut peers = BloomFilter::new();
+
+        if let Some(stored) = self.peers.get(info_hash) {
+            for peer in stored.iter().filter(|p| now.saturating_sub(p.announced) < PEER_EXPIRE_TIME) {
+                if peer.seed {
+                    seeds.insert(&peer.address.ip());
+                } else {
+                    peers.insert(&peer.address.ip());
+                }
+            }
+        }
+
+        (seeds, peers)
+    }
+
+    pub fn contains(&self, info_hash: &UID) -> bool {
+        self.peers.contains_key(info_hash)
+    }
+
+    pub fn info_hashes(&self) -> Vec<UID> {
+        self.peers.keys().cloned().collect()
+    }
+
+    //THE SAME SAMPLE IS HANDED OUT UNTIL SAMPLE_INTERVAL PASSES SO REPEATED QUERIES DONT LEAK THE WHOLE STORE
+    pub fn sample(&mut self) -> Vec<UID> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_millis();
+
+        if now.saturating_sub(self.last_sample) >= SAMPLE_INTERVAL {
+            self.remove_expired(now);
+
+            let info_hashes = self.info_hashes();
+            self.samples = if info_hashes.len() > MAX_SAMPLES {
+                let offset = gen::<usize>() % info_hashes.len();
+                info_hashes.iter().cycle().skip(offset).take(MAX_SAMPLES).cloned().collect()
+            } else {
+                info_hashes
+            };
+
+            self.last_sample = now;
+        }
+
+        self.samples.clone()
+    }
+
+    pub fn get_sample_interval(&self) -> u32 {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_millis();
+
+        (SAMPLE_INTERVAL.saturating_sub(now.saturating_sub(self.last_sample)) / 1000) as u32
+    }
+
+    //ALSO CALLED FROM THE REFRESH HANDLER, INFO HASHES THAT ARE NEVER QUERIED AGAIN WOULD OTHERWISE NEVER EXPIRE
+    pub fn remove_expired(&mut self, now: u128) {
+        for peers in self.peers.values_mut() {
+            peers.retain(|p| now.saturating_sub(p.announced) < PEER_EXPIRE_TIME);
+        }
+
+        self.peers.retain(|_, peers| !peers.is_empty());
+    }
+
+    pub fn len(&self) -> usize {
+        self.peers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.peers.is_empty()
+    }
+}
+
+impl Default for PeerStore {
+
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::utils::uid::ID_LENGTH;
+    use super::*;
+
+    #[test]
+    fn peer_expiry() {
+        let mut peer_store = PeerStore::new();
+        let address: SocketAddr = "10.0.0.1:6881".parse().unwrap();
+        let expired = UID::from([1u8; ID_LENGTH]);
+        let ahead = UID::from([2u8; ID_LENGTH]);
+        peer_store.add_peer(expired, address, false);
+        peer_store.add_peer(ahead, address, true);
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+        peer_store.peers.get_mut(&expired).unwrap()[0].announced = now-PEER_EXPIRE_TIME;
+
+        //A CLOCK THAT STEPPED BACK LEAVES ANNOUNCES IN THE FUTURE, THOSE COUNT AS FRESH
+        peer_store.peers.get_mut(&ahead).unwrap()[0].announced = now+60000;
+        assert_eq!(peer_store.get_peers(&ahead, MAX_VALUES, false), vec![address]);
+
+        peer_store.remove_expired(now);
+        assert!(!peer_store.contains(&expired));
+        assert!(peer_store.contains(&ahead));
+    }
+}
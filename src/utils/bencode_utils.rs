@@ -0,0 +1,22 @@
+use std::io;
+use rlibbencode::variables::bencode_array::BencodeArray;
+use rlibbencode::variables::bencode_bytes::BencodeBytes;
+use rlibbencode::variables::bencode_number::BencodeNumber;
+use rlibbencode::variables::bencode_object::BencodeObject;
+use rlibbencode::variables::inter::bencode_variable::{BencodeVariable, FromBencode};
+
+pub fn get_encoded(ben: &BencodeObject, key: &str) -> Option<Vec<u8>> {
+    ben.iter()
+        .find(|(k, _)| k.as_bytes() == key.as_bytes())
+        .map(|(_, v)| v.to_bencode())
+}
+
+pub fn decode_variable(buf: &[u8]) -> io::Result<Box<dyn BencodeVariable>> {
+    match buf.first() {
+        Some(b'd') => Ok(BencodeObject::from_bencode(buf)?.upcast()),
+        Some(b'l') => Ok(BencodeArray::from_bencode(buf)?.upcast()),
+        Some(b'i') => Ok(BencodeNumber::from_bencode(buf)?.upcast()),
+        Some(b'0'..=b'9') => Ok(BencodeBytes::from_bencode(buf)?.upcast()),
+        _ => Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid bencode prefix"))
+    }
+}
@@ -0,0 +1,135 @@
+use std::net::IpAddr;
+use crate::utils::hash::sha1::sha1;
+
+pub const BLOOM_FILTER_LENGTH: usize = 256;
+pub const BLOOM_FILTER_BITS: usize = BLOOM_FILTER_LENGTH*8;
+pub const BLOOM_FILTER_HASHES: usize = 2;
+
+//BEP 33 BLOOM FILTER, 2048 BITS WITH 2 HASHES TAKEN FROM THE SHA1 OF THE COMPACT IP
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct BloomFilter {
+    bits: [u8; BLOOM_FILTER_LENGTH]
+}
+
+impl BloomFilter {
+
+    pub fn new() -> Self {
+        Self {
+            bits: [0u8; BLOOM_FILTER_LENGTH]
+        }
+    }
+
+    pub fn insert(&mut self, address: &IpAddr) {
+        let hash = match address {
+            IpAddr::V4(address) => sha1(&address.octets()),
+            IpAddr::V6(address) => sha1(&address.octets())
+        };
+
+        for i in 0..BLOOM_FILTER_HASHES {
+            let index = (hash[i*2] as usize | (hash[i*2+1] as usize) << 8) % BLOOM_FILTER_BITS;
+            self.bits[index/8] |= 1 << (index%8);
+        }
+    }
+
+    pub fn merge(&mut self, other: &BloomFilter) {
+        for (bits, other) in self.bits.iter_mut().zip(other.bits.iter()) {
+            *bits |= *other;
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.iter().all(|&b| b == 0)
+    }
+
+    pub fn estimate(&self) -> f64 {
+        let zeros = self.bits.iter().map(|b| b.count_zeros() as usize).sum::<usize>();
+
+        //A FULL FILTER HAS NO MEANINGFUL ESTIMATE, CAP IT AT A SINGLE UNSET BIT
+        let zeros = zeros.max(1) as f64;
+        let m = BLOOM_FILTER_BITS as f64;
+
+        (zeros/m).ln()/(BLOOM_FILTER_HASHES as f64*(1.0-1.0/m).ln())
+    }
+
+    pub fn get_bytes(&self) -> &[u8; BLOOM_FILTER_LENGTH] {
+        &self.bits
+    }
+}
+
+impl Default for BloomFilter {
+
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<[u8; BLOOM_FILTER_LENGTH]> for BloomFilter {
+
+    fn from(bits: [u8; BLOOM_FILTER_LENGTH]) -> Self {
+        Self {
+            bits
+        }
+    }
+}
+
+//COLLECTS THE BFsd / BFpe FILTERS FROM EVERY SCRAPE RESPONSE OF A LOOKUP, THE UNION ESTIMATES THE SWARM
+#[derive(Copy, Clone, Default, Debug)]
+pub struct ScrapeEstimate {
+    seeds: BloomFilter,
+    peers: BloomFilter
+}
+
+impl ScrapeEstimate {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn merge(&mut self, seeds: Option<&BloomFilter>, peers: Option<&BloomFilter>) {
+        if let Some(seeds) = seeds {
+            self.seeds.merge(seeds);
+        }
+
+        if let Some(peers) = peers {
+            self.peers.merge(peers);
+        }
+    }
+
+    pub fn get_seeds_filter(&self) -> &BloomFilter {
+        &self.seeds
+    }
+
+    pub fn get_peers_filter(&self) -> &BloomFilter {
+        &self.peers
+    }
+
+    pub fn get_seeds(&self) -> usize {
+        self.seeds.estimate().round() as usize
+    }
+
+    pub fn get_peers(&self) -> usize {
+        self.peers.estimate().round() as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::net::{Ipv4Addr, Ipv6Addr};
+    use super::*;
+
+    #[test]
+    fn bep33_vectors() {
+        let mut filter = BloomFilter::new();
+
+        for i in 0..256u32 {
+            filter.insert(&IpAddr::V4(Ipv4Addr::new(192, 0, 2, i as u8)));
+        }
+
+        for i in 0..1000u16 {
+            filter.insert(&IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, i)));
+        }
+
+        assert!((filter.estimate()-1224.9308).abs() < 0.001);
+    }
+}
@@ -0,0 +1,460 @@
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use crate::utils::hash::sha512::Sha512;
+
+pub const PUBLIC_KEY_LENGTH: usize = 32;
+pub const SEED_LENGTH: usize = 32;
+pub const SIGNATURE_LENGTH: usize = 64;
+
+//FIELD ELEMENTS MOD 2^255-19 AS 16 LIMBS OF 16 BITS, FOLLOWS THE TWEETNACL LAYOUT
+type Gf = [i64; 16];
+
+const GF0: Gf = [0; 16];
+const GF1: Gf = [1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+
+const D: Gf = [
+    0x78a3, 0x1359, 0x4dca, 0x75eb, 0xd8ab, 0x4141, 0x0a4d, 0x0070,
+    0xe898, 0x7779, 0x4079, 0x8cc7, 0xfe73, 0x2b6f, 0x6cee, 0x5203
+];
+
+const D2: Gf = [
+    0xf159, 0x26b2, 0x9b94, 0xebd6, 0xb156, 0x8283, 0x149a, 0x00e0,
+    0xd130, 0xeef3, 0x80f2, 0x198e, 0xfce7, 0x56df, 0xd9dc, 0x2406
+];
+
+const X: Gf = [
+    0xd51a, 0x8f25, 0x2d60, 0xc956, 0xa7b2, 0x9525, 0xc760, 0x692c,
+    0xdc5c, 0xfdd6, 0xe231, 0xc0a4, 0x53fe, 0xcd6e, 0x36d3, 0x2169
+];
+
+const Y: Gf = [
+    0x6658, 0x6666, 0x6666, 0x6666, 0x6666, 0x6666, 0x6666, 0x6666,
+    0x6666, 0x6666, 0x6666, 0x6666, 0x6666, 0x6666, 0x6666, 0x6666
+];
+
+const I: Gf = [
+    0xa0b0, 0x4a0e, 0x1b27, 0xc4ee, 0xe478, 0xad2f, 0x1806, 0x2f43,
+    0xd7a7, 0x3dfb, 0x0099, 0x2b4d, 0xdf0b, 0x4fc1, 0x2480, 0x2b83
+];
+
+//GROUP ORDER
+const L: [i64; 32] = [
+    0xed, 0xd3, 0xf5, 0x5c, 0x1a, 0x63, 0x12, 0x58, 0xd6, 0x9c, 0xf7, 0xa2, 0xde, 0xf9, 0xde, 0x14,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x10
+];
+
+#[derive(Clone)]
+pub struct KeyPair {
+    seed: [u8; SEED_LENGTH],
+    public_key: [u8; PUBLIC_KEY_LENGTH]
+}
+
+impl KeyPair {
+
+    pub fn generate() -> io::Result<Self> {
+        let mut seed = [0u8; SEED_LENGTH];
+        File::open("/dev/urandom")?.read_exact(&mut seed)?;
+        Ok(Self::from_seed(seed))
+    }
+
+    pub fn from_seed(seed: [u8; SEED_LENGTH]) -> Self {
+        let d = expand_seed(&seed);
+
+        let mut p = [GF0; 4];
+        scalarbase(&mut p, &d[..32]);
+
+        let mut public_key = [0u8; PUBLIC_KEY_LENGTH];
+        pack(&mut public_key, &mut p);
+
+        Self {
+            seed,
+            public_key
+        }
+    }
+
+    pub fn get_seed(&self) -> &[u8; SEED_LENGTH] {
+        &self.seed
+    }
+
+    pub fn get_public_key(&self) -> &[u8; PUBLIC_KEY_LENGTH] {
+        &self.public_key
+    }
+
+    pub fn sign(&self, message: &[u8]) -> [u8; SIGNATURE_LENGTH] {
+        let d = expand_seed(&self.seed);
+
+        let mut hash = Sha512::new();
+        hash.update(&d, 32, 32);
+        hash.update(message, 0, message.len());
+        let mut r = hash.get_value();
+        reduce(&mut r);
+
+        let mut signature = [0u8; SIGNATURE_LENGTH];
+        let mut p = [GF0; 4];
+        scalarbase(&mut p, &r[..32]);
+        pack(&mut signature[..32], &mut p);
+
+        hash.reset();
+        hash.update(&signature, 0, 32);
+        hash.update(&self.public_key, 0, PUBLIC_KEY_LENGTH);
+        hash.update(message, 0, message.len());
+        let mut h = hash.get_value();
+        reduce(&mut h);
+
+        let mut x = [0i64; 64];
+        for i in 0..32 {
+            x[i] = r[i] as i64;
+        }
+        for i in 0..32 {
+            for j in 0..32 {
+                x[i + j] += h[i] as i64 * d[j] as i64;
+            }
+        }
+        mod_l(&mut signature[32..], &mut x);
+
+        signature
+    }
+}
+
+pub fn verify(public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    if public_key.len() != PUBLIC_KEY_LENGTH || signature.len() != SIGNATURE_LENGTH {
+        return false;
+    }
+
+    let mut q = [GF0; 4];
+    if !unpack_neg(&mut q, public_key) {
+        return false;
+    }
+
+    let mut hash = Sha512::new();
+    hash.update(signature, 0, 32);
+    hash.update(public_key, 0, PUBLIC_KEY_LENGTH);
+    hash.update(message, 0, message.len());
+    let mut h = hash.get_value();
+    reduce(&mut h);
+
+    let mut p = [GF0; 4];
+    scalarmult(&mut p, &mut q, &h[..32]);
+    scalarbase(&mut q, &signature[32..]);
+    add(&mut p, &q);
+
+    let mut t = [0u8; 32];
+    pack(&mut t, &mut p);
+
+    verify_32(&signature[..32], &t)
+}
+
+fn expand_seed(seed: &[u8; SEED_LENGTH]) -> [u8; 64] {
+    let mut hash = Sha512::new();
+    hash.update(seed, 0, SEED_LENGTH);
+    let mut d = hash.get_value();
+    d[0] &= 248;
+    d[31] &= 127;
+    d[31] |= 64;
+    d
+}
+
+fn verify_32(x: &[u8], y: &[u8]) -> bool {
+    let mut d = 0u8;
+    for i in 0..32 {
+        d |= x[i] ^ y[i];
+    }
+    d == 0
+}
+
+fn car25519(o: &mut Gf) {
+    for i in 0..16 {
+        o[i] += 1 << 16;
+        let c = o[i] >> 16;
+        if i < 15 {
+            o[i + 1] += c - 1;
+        } else {
+            o[0] += 38 * (c - 1);
+        }
+        o[i] -= c << 16;
+    }
+}
+
+fn sel25519(p: &mut Gf, q: &mut Gf, b: i64) {
+    let c = !(b - 1);
+    for i in 0..16 {
+        let t = c & (p[i] ^ q[i]);
+        p[i] ^= t;
+        q[i] ^= t;
+    }
+}
+
+fn pack25519(o: &mut [u8], n: &Gf) {
+    let mut t = *n;
+    let mut m = GF0;
+    car25519(&mut t);
+    car25519(&mut t);
+    car25519(&mut t);
+
+    for _ in 0..2 {
+        m[0] = t[0] - 0xffed;
+        for i in 1..15 {
+            m[i] = t[i] - 0xffff - ((m[i - 1] >> 16) & 1);
+            m[i - 1] &= 0xffff;
+        }
+        m[15] = t[15] - 0x7fff - ((m[14] >> 16) & 1);
+        let b = (m[15] >> 16) & 1;
+        m[14] &= 0xffff;
+        sel25519(&mut t, &mut m, 1 - b);
+    }
+
+    for i in 0..16 {
+        o[2 * i] = (t[i] & 0xff) as u8;
+        o[2 * i + 1] = (t[i] >> 8) as u8;
+    }
+}
+
+fn neq25519(a: &Gf, b: &Gf) -> bool {
+    let mut c = [0u8; 32];
+    let mut d = [0u8; 32];
+    pack25519(&mut c, a);
+    pack25519(&mut d, b);
+    !verify_32(&c, &d)
+}
+
+fn par25519(a: &Gf) -> u8 {
+    let mut d = [0u8; 32];
+    pack25519(&mut d, a);
+    d[0] & 1
+}
+
+fn unpack25519(o: &mut Gf, n: &[u8]) {
+    for i in 0..16 {
+        o[i] = n[2 * i] as i64 + ((n[2 * i + 1] as i64) << 8);
+    }
+    o[15] &= 0x7fff;
+}
+
+fn gf_add(a: &Gf, b: &Gf) -> Gf {
+    let mut o = GF0;
+    for i in 0..16 {
+        o[i] = a[i] + b[i];
+    }
+    o
+}
+
+fn gf_sub(a: &Gf, b: &Gf) -> Gf {
+    let mut o = GF0;
+    for i in 0..16 {
+        o[i] = a[i] - b[i];
+    }
+    o
+}
+
+fn gf_mul(a: &Gf, b: &Gf) -> Gf {
+    let mut t = [0i64; 31];
+    for i in 0..16 {
+        for j in 0..16 {
+            t[i + j] += a[i] * b[j];
+        }
+    }
+    for i in 0..15 {
+        t[i] += 38 * t[i + 16];
+    }
+
+    let mut o = GF0;
+    o.copy_from_slice(&t[..16]);
+    car25519(&mut o);
+    car25519(&mut o);
+    o
+}
+
+fn gf_sq(a: &Gf) -> Gf {
+    gf_mul(a, a)
+}
+
+fn inv25519(i: &Gf) -> Gf {
+    let mut c = *i;
+    for a in (0..=253).rev() {
+        c = gf_sq(&c);
+        if a != 2 && a != 4 {
+            c = gf_mul(&c, i);
+        }
+    }
+    c
+}
+
+fn pow2523(i: &Gf) -> Gf {
+    let mut c = *i;
+    for a in (0..=250).rev() {
+        c = gf_sq(&c);
+        if a != 1 {
+            c = gf_mul(&c, i);
+        }
+    }
+    c
+}
+
+fn add(p: &mut [Gf; 4], q: &[Gf; 4]) {
+    let a = gf_mul(&gf_sub(&p[1], &p[0]), &gf_sub(&q[1], &q[0]));
+    let b = gf_mul(&gf_add(&p[0], &p[1]), &gf_add(&q[0], &q[1]));
+    let c = gf_mul(&gf_mul(&p[3], &q[3]), &D2);
+    let d = gf_mul(&p[2], &q[2]);
+    let d = gf_add(&d, &d);
+    let e = gf_sub(&b, &a);
+    let f = gf_sub(&d, &c);
+    let g = gf_add(&d, &c);
+    let h = gf_add(&b, &a);
+
+    p[0] = gf_mul(&e, &f);
+    p[1] = gf_mul(&h, &g);
+    p[2] = gf_mul(&g, &f);
+    p[3] = gf_mul(&e, &h);
+}
+
+fn cswap(p: &mut [Gf; 4], q: &mut [Gf; 4], b: u8) {
+    for i in 0..4 {
+        sel25519(&mut p[i], &mut q[i], b as i64);
+    }
+}
+
+fn pack(r: &mut [u8], p: &mut [Gf; 4]) {
+    let zi = inv25519(&p[2]);
+    let tx = gf_mul(&p[0], &zi);
+    let ty = gf_mul(&p[1], &zi);
+    pack25519(r, &ty);
+    r[31] ^= par25519(&tx) << 7;
+}
+
+fn scalarmult(p: &mut [Gf; 4], q: &mut [Gf; 4], s: &[u8]) {
+    *p = [GF0, GF1, GF1, GF0];
+
+    for i in (0..256).rev() {
+        let b = (s[i / 8] >> (i & 7)) & 1;
+        cswap(p, q, b);
+        add(q, p);
+        let t = *p;
+        add(p, &t);
+        cswap(p, q, b);
+    }
+}
+
+fn scalarbase(p: &mut [Gf; 4], s: &[u8]) {
+    let mut q = [X, Y, GF1, gf_mul(&X, &Y)];
+    scalarmult(p, &mut q, s);
+}
+
+fn mod_l(r: &mut [u8], x: &mut [i64; 64]) {
+    for i in (32..64).rev() {
+        let mut carry = 0;
+        let mut j = i - 32;
+        while j < i - 12 {
+            x[j] += carry - 16 * x[i] * L[j - (i - 32)];
+            carry = (x[j] + 128) >> 8;
+            x[j] -= carry << 8;
+            j += 1;
+        }
+        x[j] += carry;
+        x[i] = 0;
+    }
+
+    let mut carry = 0;
+    for j in 0..32 {
+        x[j] += carry - (x[31] >> 4) * L[j];
+        carry = x[j] >> 8;
+        x[j] &= 255;
+    }
+
+    for j in 0..32 {
+        x[j] -= carry * L[j];
+    }
+
+    for i in 0..32 {
+        x[i + 1] += x[i] >> 8;
+        r[i] = (x[i] & 255) as u8;
+    }
+}
+
+fn reduce(r: &mut [u8; 64]) {
+    let mut x = [0i64; 64];
+    for i in 0..64 {
+        x[i] = r[i] as i64;
+    }
+    r.fill(0);
+    mod_l(r, &mut x);
+}
+
+fn unpack_neg(r: &mut [Gf; 4], p: &[u8]) -> bool {
+    r[2] = GF1;
+    unpack25519(&mut r[1], p);
+
+    let num = gf_sq(&r[1]);
+    let den = gf_mul(&num, &D);
+    let num = gf_sub(&num, &r[2]);
+    let den = gf_add(&r[2], &den);
+
+    let den2 = gf_sq(&den);
+    let den4 = gf_sq(&den2);
+    let den6 = gf_mul(&den4, &den2);
+    let mut t = gf_mul(&den6, &num);
+    t = gf_mul(&t, &den);
+
+    t = pow2523(&t);
+    t = gf_mul(&t, &num);
+    t = gf_mul(&t, &den);
+    t = gf_mul(&t, &den);
+    r[0] = gf_mul(&t, &den);
+
+    let chk = gf_mul(&gf_sq(&r[0]), &den);
+    if neq25519(&chk, &num) {
+        r[0] = gf_mul(&r[0], &I);
+    }
+
+    let chk = gf_mul(&gf_sq(&r[0]), &den);
+    if neq25519(&chk, &num) {
+        return false;
+    }
+
+    if par25519(&r[0]) == (p[31] >> 7) {
+        r[0] = gf_sub(&GF0, &r[0]);
+    }
+
+    r[3] = gf_mul(&r[0], &r[1]);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{verify, KeyPair};
+
+    fn unhex(s: &str) -> Vec<u8> {
+        (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+    }
+
+    #[test]
+    fn ed25519_vectors() {
+        //RFC 8032 SECTION 7.1, TESTS 1 AND 3
+        let vectors = [
+            ("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+             "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+             "",
+             "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b"),
+            ("c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7",
+             "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025",
+             "af82",
+             "6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a")
+        ];
+
+        for (seed, public_key, message, signature) in vectors {
+            let key_pair = KeyPair::from_seed(unhex(seed).try_into().unwrap());
+            assert_eq!(key_pair.get_public_key().to_vec(), unhex(public_key));
+
+            let message = unhex(message);
+            let sig = key_pair.sign(&message);
+            assert_eq!(sig.to_vec(), unhex(signature));
+            assert!(verify(key_pair.get_public_key(), &message, &sig));
+
+            let mut tampered = sig;
+            tampered[10] ^= 1;
+            assert!(!verify(key_pair.get_public_key(), &message, &tampered));
+            assert!(!verify(key_pair.get_public_key(), b"other", &sig));
+        }
+    }
+}
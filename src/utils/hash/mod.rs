@@ -1 +1,3 @@
 pub mod crc32c;
+pub mod sha1;
+pub mod sha512;
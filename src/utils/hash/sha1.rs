@@ -0,0 +1,159 @@
+pub const SHA1_LENGTH: usize = 20;
+
+const H: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+
+pub struct Sha1 {
+    state: [u32; 5],
+    buffer: [u8; 64],
+    buffered: usize,
+    length: u64
+}
+
+impl Sha1 {
+
+    pub fn new() -> Self {
+        Self {
+            state: H,
+            buffer: [0u8; 64],
+            buffered: 0,
+            length: 0
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.state = H;
+        self.buffered = 0;
+        self.length = 0;
+    }
+
+    pub fn update(&mut self, data: &[u8], off: usize, len: usize) {
+        let mut data = &data[off..off + len];
+        self.length += len as u64;
+
+        if self.buffered > 0 {
+            let n = (64 - self.buffered).min(data.len());
+            self.buffer[self.buffered..self.buffered + n].copy_from_slice(&data[..n]);
+            self.buffered += n;
+            data = &data[n..];
+
+            if self.buffered < 64 {
+                return;
+            }
+
+            let block = self.buffer;
+            Self::compress(&mut self.state, &block);
+            self.buffered = 0;
+        }
+
+        while data.len() >= 64 {
+            Self::compress(&mut self.state, &data[..64]);
+            data = &data[64..];
+        }
+
+        self.buffer[..data.len()].copy_from_slice(data);
+        self.buffered = data.len();
+    }
+
+    pub fn get_value(&self) -> [u8; SHA1_LENGTH] {
+        let mut state = self.state;
+        let mut buffer = self.buffer;
+        let mut buffered = self.buffered;
+
+        buffer[buffered] = 0x80;
+        buffered += 1;
+
+        if buffered > 56 {
+            buffer[buffered..].fill(0);
+            Self::compress(&mut state, &buffer);
+            buffered = 0;
+        }
+
+        buffer[buffered..56].fill(0);
+        buffer[56..].copy_from_slice(&(self.length * 8).to_be_bytes());
+        Self::compress(&mut state, &buffer);
+
+        let mut digest = [0u8; SHA1_LENGTH];
+        for (i, word) in state.iter().enumerate() {
+            digest[i * 4..i * 4 + 4].copy_from_slice(&word.to_be_bytes());
+        }
+        digest
+    }
+
+    fn compress(state: &mut [u32; 5], block: &[u8]) {
+        let mut w = [0u32; 80];
+
+        for (i, chunk) in block.chunks(4).take(16).enumerate() {
+            w[i] = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = *state;
+
+        for (i, word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6)
+            };
+
+            let temp = a.rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(*word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+
+        state[0] = state[0].wrapping_add(a);
+        state[1] = state[1].wrapping_add(b);
+        state[2] = state[2].wrapping_add(c);
+        state[3] = state[3].wrapping_add(d);
+        state[4] = state[4].wrapping_add(e);
+    }
+}
+
+impl Default for Sha1 {
+
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn sha1(data: &[u8]) -> [u8; SHA1_LENGTH] {
+    let mut hash = Sha1::new();
+    hash.update(data, 0, data.len());
+    hash.get_value()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{sha1, Sha1};
+
+    fn hex(digest: &[u8]) -> String {
+        digest.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn sha1_vectors() {
+        assert_eq!(hex(&sha1(b"")), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+        assert_eq!(hex(&sha1(b"abc")), "a9993e364706816aba3e25717850c26c9cd0d89d");
+        assert_eq!(hex(&sha1(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq")), "84983e441c3bd26ebaae4aa1f95129e5e54670f1");
+
+        let data = [b'a'; 1000];
+        let mut hash = Sha1::new();
+        for _ in 0..1000 {
+            hash.update(&data, 0, 7);
+            hash.update(&data, 7, 993);
+        }
+        assert_eq!(hex(&hash.get_value()), "34aa973cd4c4daa4f61eeb2bdbad27316534016f");
+    }
+}
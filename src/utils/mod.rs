@@ -2,8 +2,13 @@ pub mod hash;
 pub mod net;
 pub mod uid;
 pub mod node;
+pub mod node_state;
 pub mod node_utils;
 pub mod random;
 pub mod linked_hashmap;
 pub mod byte_wrapper;
+pub mod bencode_utils;
 pub mod spam_throttle;
+pub mod token_manager;
+pub mod ed25519;
+pub mod bloom_filter;
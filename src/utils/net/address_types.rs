@@ -1,8 +1,35 @@
+use std::net::SocketAddr;
+
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub enum AddressTypes {
     Ipv4,
     Ipv6
 }
 
+impl AddressTypes {
+
+    pub fn from_want(name: &str) -> Option<Self> {
+        match name {
+            "n4" => Some(Self::Ipv4),
+            "n6" => Some(Self::Ipv6),
+            _ => None
+        }
+    }
+
+    pub fn from_address(address: &SocketAddr) -> Self {
+        match address {
+            SocketAddr::V4(_) => Self::Ipv4,
+            SocketAddr::V6(_) => Self::Ipv6
+        }
+    }
+
+    pub fn want(&self) -> &str {
+        match self {
+            Self::Ipv4 => "n4",
+            Self::Ipv6 => "n6"
+        }
+    }
+}
+
 pub const IPV4_LENGTH: usize = 4;
 pub const IPV6_LENGTH: usize = 16;
@@ -2,12 +2,15 @@ use std::net::{IpAddr, SocketAddr};
 use std::time::{SystemTime, UNIX_EPOCH};
 use std::{cmp, fmt};
 use std::fmt::Formatter;
+use crate::kad::server::VERSION_LENGTH;
+use super::node_state::NodeState;
 use super::uid::UID;
 use super::hash::crc32c::Crc32c;
 
 pub const V4_MASK: [u8; 4] = [0x03, 0x0f, 0x3f, 0xff];
 pub const V6_MASK: [u8; 8] = [0x01, 0x03, 0x07, 0x0f, 0x1f, 0x3f, 0x7f, 0xff];
-const QUERY_TIME: u128 = 3600000;
+pub const QUERY_TIME: u128 = 900000; //BEP 5, A NODE THAT ANSWERED WITHIN 15 MINUTES IS GOOD
+pub const MAX_STALE_COUNT: u32 = 3; //FAILED QUERIES IN A ROW BEFORE A NODE IS BAD
 
 #[derive(Debug, Copy, Clone)]
 pub struct Node {
@@ -15,6 +18,8 @@ pub struct Node {
     pub(crate) address: SocketAddr,
     pub(crate) stale: u32,
     pub(crate) last_seen: u128,
+    pub(crate) last_response: u128,
+    pub(crate) version: Option<[u8; VERSION_LENGTH]>
 }
 
 impl Node {
@@ -25,6 +30,8 @@ impl Node {
             address,
             stale: 0,
             last_seen: 0,
+            last_response: 0,
+            version: None
         }
     }
 
@@ -60,20 +67,68 @@ impl Node {
         (uid_crc & 0xff_ff_f8_00) == 0
     }
 
+    //ANY ACTIVITY, A QUERY FROM THE NODE DOESNT PROVE IT ANSWERS SO THE FAILED QUERIES ARE KEPT
     pub fn seen(&mut self) {
-        self.stale = 0;
         self.last_seen = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .expect("Time went backwards")
             .as_millis();
     }
 
+    pub fn responded(&mut self) {
+        self.seen();
+        self.stale = 0;
+        self.last_response = self.last_seen;
+    }
+
     pub fn mark_stale(&mut self) {
         self.stale += 1;
     }
 
-    pub fn has_queried(&self, now: u128) -> bool {
-        self.last_seen > 0 && now - self.last_seen < QUERY_TIME
+    //GOOD ONCE IT HAS ANSWERED US AND BEEN ACTIVE WITHIN query_time, NODES THAT ONLY QUERIED US STAY QUESTIONABLE
+    pub fn get_state(&self, now: u128, query_time: u128) -> NodeState {
+        if self.stale >= MAX_STALE_COUNT {
+            NodeState::Bad
+
+        } else if self.last_response > 0 && now.saturating_sub(self.last_seen) < query_time {
+            NodeState::Good
+
+        } else {
+            NodeState::Questionable
+        }
+    }
+
+    pub fn has_queried(&self, now: u128, query_time: u128) -> bool {
+        self.get_state(now, query_time) == NodeState::Good
+    }
+
+    pub fn get_last_seen(&self) -> u128 {
+        self.last_seen
+    }
+
+    pub fn get_last_response(&self) -> u128 {
+        self.last_response
+    }
+
+    pub fn get_stale(&self) -> u32 {
+        self.stale
+    }
+
+    pub fn set_version(&mut self, version: [u8; VERSION_LENGTH]) {
+        self.version = Some(version);
+    }
+
+    pub fn get_version(&self) -> Option<[u8; VERSION_LENGTH]> {
+        self.version
+    }
+
+    //THE FIRST 2 BYTES OF V ARE THE CLIENT CODE, THE LAST 2 ARE THE BIG ENDIAN CLIENT VERSION
+    pub fn get_client(&self) -> Option<String> {
+        self.version.map(|version| String::from_utf8_lossy(&version[..2]).to_string())
+    }
+
+    pub fn get_client_version(&self) -> Option<u16> {
+        self.version.map(|version| u16::from_be_bytes([version[2], version[3]]))
     }
 
     pub fn verify(&self, other: &Self) -> bool {
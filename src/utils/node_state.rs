@@ -0,0 +1,7 @@
+//BEP 5 NODE HEALTH, ONLY GOOD NODES ARE TRUSTED WITHOUT A PING
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum NodeState {
+    Good,
+    Questionable,
+    Bad
+}
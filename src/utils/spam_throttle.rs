@@ -3,27 +3,43 @@ use std::net::IpAddr;
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
-const BURST: usize = 10;
-const PER_SECOND: usize = 2;
+pub const BURST: usize = 10;
+pub const PER_SECOND: usize = 2;
 
 #[derive(Clone)]
 pub struct SpamThrottle {
     hit_counter: Arc<Mutex<HashMap<IpAddr, usize>>>,
-    last_decay_time: Arc<Mutex<Instant>>
+    last_decay_time: Arc<Mutex<Instant>>,
+    burst: usize,
+    per_second: usize
 }
 
 impl SpamThrottle {
 
     pub fn new() -> Self {
+        Self::with_rate(BURST, PER_SECOND)
+    }
+
+    pub fn with_rate(burst: usize, per_second: usize) -> Self {
         Self {
             hit_counter: Arc::new(Mutex::new(HashMap::new())),
-            last_decay_time: Arc::new(Mutex::new(Instant::now()))
+            last_decay_time: Arc::new(Mutex::new(Instant::now())),
+            burst,
+            per_second
         }
     }
 
+    pub fn get_burst(&self) -> usize {
+        self.burst
+    }
+
+    pub fn get_per_second(&self) -> usize {
+        self.per_second
+    }
+
     pub fn add_and_test(&self, address: IpAddr) -> bool {
         let count = self.saturating_add(address);
-        count >= BURST
+        count >= self.burst
     }
 
     pub fn remove(&self, address: IpAddr) {
@@ -33,7 +49,7 @@ impl SpamThrottle {
 
     pub fn test(&self, address: IpAddr) -> bool {
         let hit_counter = self.hit_counter.lock().unwrap();
-        hit_counter.get(&address).cloned().unwrap_or(0) >= BURST
+        hit_counter.get(&address).cloned().unwrap_or(0) >= self.burst
     }
 
     pub fn calculate_delay_and_add(&self, address: IpAddr) -> usize {
@@ -41,12 +57,8 @@ impl SpamThrottle {
         let counter = hit_counter.entry(address).or_insert(0);
         *counter += 1;
 
-        let diff = if *counter > BURST {
-            *counter - BURST
-        } else {
-            0
-        };
-        (diff * 1000) / PER_SECOND
+        let diff = (*counter).saturating_sub(self.burst);
+        (diff * 1000) / self.per_second.max(1)
     }
 
     pub fn saturating_dec(&self, address: IpAddr) {
@@ -63,7 +75,7 @@ impl SpamThrottle {
     pub fn saturating_add(&self, address: IpAddr) -> usize {
         let mut hit_counter = self.hit_counter.lock().unwrap();
         let counter = hit_counter.entry(address).or_insert(0);
-        *counter = (*counter + 1).min(BURST);
+        *counter = (*counter + 1).min(self.burst);
         *counter
     }
 
@@ -78,7 +90,7 @@ impl SpamThrottle {
 
         *last_decay_time = now;
 
-        let delta_count = (delta_t * PER_SECOND as u64) as usize;
+        let delta_count = (delta_t * self.per_second as u64) as usize;
 
         let mut hit_counter = self.hit_counter.lock().unwrap();
         hit_counter.retain(|_, v| *v > delta_count);
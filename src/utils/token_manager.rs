@@ -0,0 +1,97 @@
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::net::IpAddr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const TOKEN_LENGTH: usize = 8;
+pub const TOKEN_ROTATION_TIME: u128 = 300000;
+
+//EACH SECRET IS A RANDOMLY KEYED SIPHASH, TOKENS STAY VALID UNTIL THE SECRET THEY WERE SIGNED WITH IS ROTATED OUT TWICE
+pub struct TokenManager {
+    secret: RandomState,
+    previous_secret: RandomState,
+    last_rotation: u128
+}
+
+impl TokenManager {
+
+    pub fn new() -> Self {
+        Self {
+            secret: RandomState::new(),
+            previous_secret: RandomState::new(),
+            last_rotation: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("Time went backwards")
+                .as_millis()
+        }
+    }
+
+    pub fn generate_token(&mut self, address: IpAddr) -> [u8; TOKEN_LENGTH] {
+        self.update();
+        Self::sign(&self.secret, address)
+    }
+
+    pub fn validate_token(&mut self, token: &[u8], address: IpAddr) -> bool {
+        self.update();
+        token == Self::sign(&self.secret, address) || token == Self::sign(&self.previous_secret, address)
+    }
+
+    pub fn rotate(&mut self) {
+        self.previous_secret = std::mem::replace(&mut self.secret, RandomState::new());
+        self.last_rotation = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_millis();
+    }
+
+    fn update(&mut self) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_millis();
+
+        let elapsed = now.saturating_sub(self.last_rotation);
+
+        if elapsed >= TOKEN_ROTATION_TIME*2 {
+            self.rotate();
+            self.rotate();
+
+        } else if elapsed >= TOKEN_ROTATION_TIME {
+            self.rotate();
+        }
+    }
+
+    fn sign(secret: &RandomState, address: IpAddr) -> [u8; TOKEN_LENGTH] {
+        secret.hash_one(address).to_be_bytes()
+    }
+}
+
+impl Default for TokenManager {
+
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::net::IpAddr;
+    use super::TokenManager;
+
+    #[test]
+    fn token_rotation() {
+        let address = IpAddr::from([1, 2, 3, 4]);
+        let mut manager = TokenManager::new();
+        let token = manager.generate_token(address);
+
+        assert!(manager.validate_token(&token, address));
+        assert!(!manager.validate_token(&token, IpAddr::from([1, 2, 3, 5])));
+
+        manager.rotate();
+        assert!(manager.validate_token(&token, address));
+
+        manager.rotate();
+        assert!(!manager.validate_token(&token, address));
+    }
+}
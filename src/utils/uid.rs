@@ -1,5 +1,10 @@
 use std::fmt;
 use std::fmt::Formatter;
+use std::hash::{Hash, Hasher};
+use std::net::IpAddr;
+use super::hash::crc32c::Crc32c;
+use super::random::gen_array;
+use super::node::{V4_MASK, V6_MASK};
 
 pub const ID_LENGTH: usize = 20;
 
@@ -40,6 +45,41 @@ impl TryFrom<&str> for UID {
 
 impl UID {
 
+    //BEP 42 ID FOR THE GIVEN EXTERNAL ADDRESS, EVERY BIT THE CRC DOESNT COVER IS TAKEN FROM THE SEED
+    //SO THE SAME SEED AND ADDRESS ALWAYS GIVE THE SAME ID
+    pub fn derive_secure(address: IpAddr, seed: &[u8; ID_LENGTH]) -> Self {
+        let mut ip: Vec<u8> = match address {
+            IpAddr::V4(v4) => v4.octets().to_vec(),
+            IpAddr::V6(v6) => v6.octets()[..V6_MASK.len()].to_vec()
+        };
+
+        let mask: &[u8] = if ip.len() == 4 {
+            &V4_MASK
+        } else {
+            &V6_MASK
+        };
+
+        for i in 0..mask.len() {
+            ip[i] &= mask[i];
+        }
+
+        let r = seed[19] & 0x7;
+        ip[0] |= r << 5;
+
+        let mut c = Crc32c::new();
+        c.update(&ip, 0, ip.len());
+        let crc = c.get_value();
+
+        let mut bid = *seed;
+        bid[0] = (crc >> 24) as u8;
+        bid[1] = (crc >> 16) as u8;
+        bid[2] = ((crc >> 8) as u8 & 0xF8) | (seed[2] & 0x7);
+
+        Self {
+            bid
+        }
+    }
+
     pub fn distance(&self, k: &UID) -> usize {
         ID_LENGTH*8-self.xor(k).first_set_bit_index()
     }
@@ -93,6 +133,19 @@ impl UID {
         self.xor(&UID { bid: result })
     }
 
+    //RANDOM ID AT EXACTLY THE GIVEN DISTANCE, EVERY BIT BELOW THE FIRST DIFFERING ONE IS RANDOM
+    pub fn generate_random_node_id_by_distance(&self, distance: usize) -> UID {
+        let mut result = gen_array::<u8, ID_LENGTH>();
+        let bit = ID_LENGTH*8-distance.clamp(1, ID_LENGTH*8);
+
+        for i in 0..bit {
+            result[i/8] &= !(0x80 >> (i%8));
+        }
+
+        result[bit/8] |= 0x80 >> (bit%8);
+        self.xor(&UID { bid: result })
+    }
+
     pub fn bytes(&self) -> [u8; ID_LENGTH] {
         self.bid
     }
@@ -123,6 +176,15 @@ impl PartialEq for UID {
     }
 }
 
+impl Eq for UID {}
+
+impl Hash for UID {
+
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.bid.hash(state);
+    }
+}
+
 impl fmt::Display for UID {
 
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {